@@ -0,0 +1,197 @@
+use uuid::Uuid;
+
+use crate::todo_func::{AppState, Content, TodoApp};
+
+// Near matches may differ by one typo for every this many characters, so short notes have to match exactly.
+const FUZZY_CHARS_PER_EDIT: usize = 8;
+
+// Case and runs of whitespace don't make a note different.
+pub fn normalize_text(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+// How many characters have to be inserted, removed or replaced to turn one text into the other.
+fn edit_distance(a: &[char], b: &[char]) -> usize {
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.iter().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let replaced = previous[j] + usize::from(a_char != b_char);
+            current.push(replaced.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+pub fn is_duplicate(a: &str, b: &str, fuzzy: bool) -> bool {
+    let (a, b) = (normalize_text(a), normalize_text(b));
+    if a == b {
+        return true;
+    }
+    if !fuzzy {
+        return false;
+    }
+
+    let (a, b): (Vec<char>, Vec<char>) = (a.chars().collect(), b.chars().collect());
+    let allowed = a.len().max(b.len()) / FUZZY_CHARS_PER_EDIT;
+    a.len().abs_diff(b.len()) <= allowed && edit_distance(&a, &b) <= allowed
+}
+
+// Archived notes are left out, a finished checklist item may well be added again.
+impl AppState {
+    pub fn find_duplicate(&self, text: &str, fuzzy: bool) -> Option<usize> {
+        (0..self.list.len()).find(|&i| !self.list[i].archived && is_duplicate(&self.list[i].text, text, fuzzy))
+    }
+
+    // Notes that duplicate each other, each group in page order.
+    pub fn duplicate_groups(&self, fuzzy: bool) -> Vec<Vec<usize>> {
+        let mut grouped = vec![false; self.list.len()];
+        let mut groups = Vec::new();
+
+        for i in 0..self.list.len() {
+            if grouped[i] || self.list[i].archived {
+                continue;
+            }
+
+            let group: Vec<usize> = std::iter::once(i)
+                .chain((i + 1..self.list.len()).filter(|&j| {
+                    !grouped[j] && !self.list[j].archived && is_duplicate(&self.list[i].text, &self.list[j].text, fuzzy)
+                }))
+                .collect();
+
+            if group.len() > 1 {
+                for &j in &group {
+                    grouped[j] = true;
+                }
+                groups.push(group);
+            }
+        }
+        groups
+    }
+
+    // Folds `duplicate` into `keep` and returns it so it can go to the trash. The kept note takes its tags, the
+    // higher priority, whatever due date, repeat rule or reminder it lacks and its subtasks, and stays checked
+    // only if both were.
+    pub fn merge_duplicate(&mut self, keep: Uuid, duplicate: Uuid) -> Option<Content> {
+        let index = self.index_of(duplicate)?;
+        if self.subtree(index).contains(&self.index_of(keep)?) {
+            return None;
+        }
+
+        let children = self.children(Some(index));
+        let mut subtasks = self.take_notes(&children, false);
+        let keep_index = self.index_of(keep)?;
+        let insert_at = self.subtree(keep_index).end;
+        for content in &mut subtasks {
+            content.depth += self.list[keep_index].depth + 1;
+        }
+        self.list.splice(insert_at..insert_at, subtasks);
+
+        let removed = self.list.remove(self.index_of(duplicate)?);
+        let keep_index = self.index_of(keep)?;
+        let kept = &mut self.list[keep_index];
+        for tag in &removed.tags {
+            if !kept.has_tag(tag) {
+                kept.tags.push(tag.clone());
+            }
+        }
+        kept.priority = kept.priority.max(removed.priority);
+        kept.is_checked &= removed.is_checked;
+        kept.due = kept.due.or(removed.due);
+        kept.recurrence = kept.recurrence.or(removed.recurrence);
+        kept.reminder = kept.reminder.or(removed.reminder);
+
+        self.normalize_depths();
+        Some(removed)
+    }
+}
+
+impl TodoApp {
+    // Each group is merged into its first note, and the rest go to the trash.
+    pub fn merge_duplicates(&mut self, groups: &[Vec<Uuid>]) {
+        self.record_history("Merge duplicates");
+
+        for group in groups {
+            let Some((keep, duplicates)) = group.split_first() else {
+                continue;
+            };
+            for duplicate in duplicates {
+                if let Some(content) = self.state.merge_duplicate(*keep, *duplicate) {
+                    self.state_list.trash_note(vec![content]);
+                }
+            }
+        }
+
+        self.update_state();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{depths, outline};
+    use crate::todo_func::{Page, Priority};
+
+    #[test]
+    fn case_and_whitespace_are_ignored() {
+        assert!(is_duplicate("Buy  Milk ", "buy milk", false));
+        assert!(!is_duplicate("buy milk", "buy milk!", false));
+        assert_eq!(normalize_text("  Send\tthe   Report "), "send the report");
+    }
+
+    #[test]
+    fn fuzzy_matches_allow_a_typo_per_eight_characters() {
+        assert!(is_duplicate("write release notes", "write relase notes", true));
+        assert!(!is_duplicate("write release notes", "write relase notes", false));
+        assert!(!is_duplicate("milk", "silk", true));
+        assert!(!is_duplicate("write release notes", "write the notes", true));
+        assert_eq!(edit_distance(&['k', 'i', 't'], &['s', 'i', 't', 's']), 2);
+    }
+
+    #[test]
+    fn archived_notes_are_not_duplicates() {
+        let mut state = outline(&[("Buy milk", 0), ("Other", 0), ("buy MILK", 0), ("Other", 0)]);
+        assert_eq!(state.find_duplicate("BUY MILK", false), Some(0));
+        assert_eq!(state.duplicate_groups(false), vec![vec![0, 2], vec![1, 3]]);
+
+        state.list[0].archived = true;
+        assert_eq!(state.find_duplicate("BUY MILK", false), Some(2));
+        assert_eq!(state.duplicate_groups(false), vec![vec![1, 3]]);
+    }
+
+    #[test]
+    fn merging_keeps_the_first_note_with_everything_from_the_other() {
+        let mut state = outline(&[("a task", 0), ("sub1", 1), ("other", 0), ("A  TASK", 0), ("sub2", 1), ("subsub", 2)]);
+        state.list[0].tags = vec![String::from("work")];
+        state.list[3].tags = vec![String::from("work"), String::from("urgent")];
+        state.list[3].priority = Priority::High;
+        state.list[3].is_checked = true;
+        let (keep, duplicate) = (state.list[0].id, state.list[3].id);
+
+        let removed = state.merge_duplicate(keep, duplicate).unwrap();
+        assert_eq!(removed.text, "A  TASK");
+        assert_eq!(depths(&state), vec![("a task", 0), ("sub1", 1), ("sub2", 1), ("subsub", 2), ("other", 0)]);
+        assert_eq!(state.list[0].tags, vec!["work", "urgent"]);
+        assert!(state.list[0].priority == Priority::High);
+        assert!(!state.list[0].is_checked);
+    }
+
+    #[test]
+    fn merged_duplicates_go_to_the_trash() {
+        let mut app = TodoApp::default();
+        let page = Page::new(String::from("Work"));
+        app.state_list.current_page = Some(page.id);
+        app.state_list.list.push(page);
+        app.state = outline(&[("Call Sam", 0), ("call sam", 0), ("Email", 0), ("CALL SAM", 0)]);
+
+        let groups: Vec<Vec<Uuid>> = app.state.duplicate_groups(false).into_iter()
+            .map(|group| group.into_iter().map(|index| app.state.list[index].id).collect())
+            .collect();
+        app.merge_duplicates(&groups);
+
+        assert_eq!(depths(&app.state), vec![("Call Sam", 0), ("Email", 0)]);
+        assert_eq!(app.state_list.trash.len(), 2);
+        assert_eq!(app.state_list.list[0].state.list.len(), 2);
+    }
+}
@@ -0,0 +1,147 @@
+use std::collections::VecDeque;
+use uuid::Uuid;
+
+use crate::todo_func::{Page, StateList, TrashItem};
+
+pub const DEFAULT_HISTORY_DEPTH: usize = 50;
+
+// A copy of every page taken right before a change, so the change can be rolled back as a whole.
+pub struct Snapshot {
+    pub label: String,
+    pages: Vec<Page>,
+    current_page: Option<Uuid>,
+    trash: Vec<TrashItem>,
+}
+
+impl Snapshot {
+    fn take(label: &str, state_list: &StateList) -> Self {
+        Self {
+            label: String::from(label),
+            pages: state_list.list.clone(),
+            current_page: state_list.current_page,
+            trash: state_list.trash.clone(),
+        }
+    }
+
+    // Puts the snapshot back into the state list and returns what it replaced.
+    fn restore(self, state_list: &mut StateList) -> Self {
+        let replaced = Self::take(&self.label, state_list);
+        state_list.list = self.pages;
+        state_list.current_page = self.current_page;
+        state_list.trash = self.trash;
+        replaced
+    }
+}
+
+#[derive(Default)]
+pub struct History {
+    undo_stack: VecDeque<Snapshot>,
+    redo_stack: Vec<Snapshot>,
+}
+
+impl History {
+    pub fn record(&mut self, label: &str, state_list: &StateList, depth: usize) {
+        self.undo_stack.push_back(Snapshot::take(label, state_list));
+        while self.undo_stack.len() > depth {
+            self.undo_stack.pop_front();
+        }
+        self.redo_stack.clear();
+    }
+
+    pub fn undo(&mut self, state_list: &mut StateList) -> bool {
+        let Some(snapshot) = self.undo_stack.pop_back() else {
+            return false;
+        };
+        self.redo_stack.push(snapshot.restore(state_list));
+        true
+    }
+
+    pub fn redo(&mut self, state_list: &mut StateList) -> bool {
+        let Some(snapshot) = self.redo_stack.pop() else {
+            return false;
+        };
+        self.undo_stack.push_back(snapshot.restore(state_list));
+        true
+    }
+
+    pub fn undo_label(&self) -> Option<&str> {
+        self.undo_stack.back().map(|snapshot| snapshot.label.as_str())
+    }
+
+    pub fn redo_label(&self) -> Option<&str> {
+        self.redo_stack.last().map(|snapshot| snapshot.label.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn titles(state_list: &StateList) -> Vec<&str> {
+        state_list.list.iter().map(|page| page.title.as_str()).collect()
+    }
+
+    // Records a change before adding a page, the way the app does.
+    fn add_page(history: &mut History, state_list: &mut StateList, title: &str, depth: usize) {
+        history.record(&format!("Add {title}"), state_list, depth);
+        state_list.list.push(Page::new(String::from(title)));
+    }
+
+    #[test]
+    fn depth_drops_the_oldest_change() {
+        let mut history = History::default();
+        let mut state_list = StateList::default();
+        for title in ["a", "b", "c"] {
+            add_page(&mut history, &mut state_list, title, 2);
+        }
+
+        assert!(history.undo(&mut state_list));
+        assert!(history.undo(&mut state_list));
+        assert_eq!(titles(&state_list), vec!["a"]);
+        assert!(!history.undo(&mut state_list));
+        assert_eq!(history.undo_label(), None);
+    }
+
+    #[test]
+    fn undo_and_redo_round_trip() {
+        let mut history = History::default();
+        let mut state_list = StateList::default();
+        add_page(&mut history, &mut state_list, "a", DEFAULT_HISTORY_DEPTH);
+        add_page(&mut history, &mut state_list, "b", DEFAULT_HISTORY_DEPTH);
+
+        assert!(history.undo(&mut state_list));
+        assert_eq!(titles(&state_list), vec!["a"]);
+        assert_eq!(history.redo_label(), Some("Add b"));
+
+        assert!(history.redo(&mut state_list));
+        assert_eq!(titles(&state_list), vec!["a", "b"]);
+        assert_eq!(history.undo_label(), Some("Add b"));
+        assert_eq!(history.redo_label(), None);
+    }
+
+    #[test]
+    fn recording_clears_the_redo_stack() {
+        let mut history = History::default();
+        let mut state_list = StateList::default();
+        add_page(&mut history, &mut state_list, "a", DEFAULT_HISTORY_DEPTH);
+        assert!(history.undo(&mut state_list));
+
+        add_page(&mut history, &mut state_list, "b", DEFAULT_HISTORY_DEPTH);
+        assert_eq!(history.redo_label(), None);
+        assert!(!history.redo(&mut state_list));
+        assert_eq!(titles(&state_list), vec!["b"]);
+    }
+
+    #[test]
+    fn empty_history_does_nothing() {
+        let mut history = History::default();
+        let mut state_list = StateList::default();
+        state_list.list.push(Page::new(String::from("a")));
+
+        assert!(!history.undo(&mut state_list));
+        assert!(!history.redo(&mut state_list));
+        assert_eq!(history.undo_label(), None);
+        assert_eq!(history.redo_label(), None);
+        assert_eq!(titles(&state_list), vec!["a"]);
+    }
+}
@@ -1,26 +1,101 @@
-use std::{fs::File, io::{self, Read, Write}, path::Path};
-use crate::todo_func::{AppState, StateList, Theme};
+use std::{fs::{self, File}, io::{self, Read, Write}, path::{Path, PathBuf}, time::{SystemTime, UNIX_EPOCH}};
+use serde_json::Value;
+use crate::todo_func::{Page, StateList, Theme};
+
+mod migration;
+pub use migration::{STATE_LIST_VERSION, THEME_VERSION};
 
 const STATE_LIST_KEY: &str = "state_list";
 const CONFIG_PATH: &str = "config.json";
+const BACKUP_FILE_PREFIX: &str = "state_list_backup";
+const CONFIG_BACKUP_PREFIX: &str = "config_backup";
+
+// Describes what happened when the saved state list could not be read as a whole.
+pub struct Recovery {
+    pub error: String,
+    pub backup_path: Option<PathBuf>,
+    pub salvaged_pages: usize,
+    // Unknown when the data is too damaged to tell its pages apart.
+    pub lost_pages: Option<usize>,
+}
 
 pub fn read_state_list(cc: &eframe::CreationContext<'_>) -> io::Result<StateList> {
     if let Some(storage) = cc.storage{
         if let Some(list) = storage.get_string(STATE_LIST_KEY){
-            return serde_json::from_str(&list)
-                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e));
+            return parse_state_list(&list);
         }
     } 
     Ok(StateList::default())
 }
 
+fn parse_state_list(json: &str) -> io::Result<StateList> {
+    let value: Value = serde_json::from_str(json).map_err(invalid_data)?;
+    serde_json::from_value(migration::migrate_state_list(value)?).map_err(invalid_data)
+}
+
+// Copies the unreadable data to a backup file, then keeps every page that can still be read on its own.
+pub fn recover_state_list(cc: &eframe::CreationContext<'_>, error: &io::Error) -> (StateList, Recovery) {
+    let raw = cc.storage.and_then(|storage| storage.get_string(STATE_LIST_KEY)).unwrap_or_default();
+
+    let backup_path = match write_backup(&raw) {
+        Ok(path) => Some(path),
+        Err(e) => {
+            eprintln!("Failed to back up unreadable state list: {e}");
+            None
+        }
+    };
+
+    let (state_list, lost_pages) = salvage_state_list(&raw);
+    let recovery = Recovery {
+        error: error.to_string(),
+        backup_path,
+        salvaged_pages: state_list.list.len(),
+        lost_pages,
+    };
+    (state_list, recovery)
+}
+
+fn backup_path(prefix: &str) -> PathBuf {
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or_default();
+    PathBuf::from(format!("{prefix}_{timestamp}.json"))
+}
+
+fn write_backup(raw: &str) -> io::Result<PathBuf> {
+    let path = backup_path(BACKUP_FILE_PREFIX);
+    let mut file = File::create(&path)?;
+    file.write_all(raw.as_bytes())?;
+    Ok(path)
+}
+
+// Returns the pages that could still be read and how many could not. Data that isn't valid JSON anymore
+// can't be split into pages, so nothing is kept and the number of lost pages is unknown.
+fn salvage_state_list(raw: &str) -> (StateList, Option<usize>) {
+    let mut state_list = StateList::default();
+    let Some(pages) = serde_json::from_str::<Value>(raw).ok().and_then(|value| migration::migrate_pages_separately(&value)) else {
+        return (state_list, None);
+    };
+
+    let mut lost_pages = 0;
+    for page in pages {
+        match serde_json::from_value::<Page>(page) {
+            Ok(page) => state_list.list.push(page),
+            Err(_) => lost_pages += 1,
+        }
+    }
+    (state_list, Some(lost_pages))
+}
+
+fn invalid_data(e: serde_json::Error) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, e)
+}
+
 pub fn save_state_list(state_list: &StateList, storage: &mut dyn eframe::Storage) -> io::Result<()> {
     match serde_json::to_string_pretty(state_list) {
         Ok(json) => {
             storage.set_string(STATE_LIST_KEY, json);
             Ok(())
         },
-        Err(e) => Err(io::Error::new(io::ErrorKind::Other, e.to_string()))
+        Err(e) => Err(io::Error::other(e.to_string()))
     }
 }
 
@@ -29,26 +104,87 @@ pub fn read_theme() -> io::Result<Theme> {
         let mut file = File::open(CONFIG_PATH)?;
         let mut contents = String::new();
         file.read_to_string(&mut contents)?;
-        serde_json::from_str(&contents).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+        let value: Value = serde_json::from_str(&contents).map_err(invalid_data)?;
+        serde_json::from_value(migration::migrate_theme(value)?).map_err(invalid_data)
     } else {
         Ok(Theme::default())
     }
 }
 
+// Moves an unreadable config file aside, so saving the default settings doesn't overwrite it.
+pub fn back_up_theme() -> io::Result<PathBuf> {
+    let path = backup_path(CONFIG_BACKUP_PREFIX);
+    fs::rename(CONFIG_PATH, &path)?;
+    Ok(path)
+}
+
 pub fn save_theme(theme: &Theme) -> io::Result<()> {
     let json = serde_json::to_string_pretty(theme)?;
     let mut file = File::create(CONFIG_PATH)?;
     file.write_all(json.as_bytes())
 }
 
-pub fn state_to_json_string(state: &AppState) -> String {
-    serde_json::to_string_pretty(state).unwrap_or_default()
-}
 
-pub fn json_string_to_state(json: Option<&String>) -> io::Result<AppState> {
-    if let Some(text) = json {
-        return serde_json::from_str(text)
-            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e));
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use uuid::Uuid;
+
+    fn titles(state_list: &StateList) -> Vec<&str> {
+        state_list.list.iter().map(|page| page.title.as_str()).collect()
+    }
+
+    #[test]
+    fn broken_json_loses_an_unknown_number_of_pages() {
+        let (state_list, lost_pages) = salvage_state_list(r#"{"schema_version": 3, "list": [{"id": "#);
+        assert!(state_list.list.is_empty());
+        assert_eq!(lost_pages, None);
+    }
+
+    #[test]
+    fn current_pages_are_salvaged_one_by_one() {
+        let raw = json!({
+            "schema_version": 3,
+            "list": [
+                { "id": Uuid::new_v4(), "title": "Kept", "state": { "list": [] } },
+                { "id": "not a uuid", "title": "Broken", "state": { "list": [] } },
+            ],
+            "current_page": null,
+        });
+
+        let (state_list, lost_pages) = salvage_state_list(&raw.to_string());
+        assert_eq!(titles(&state_list), vec!["Kept"]);
+        assert_eq!(lost_pages, Some(1));
+    }
+
+    #[test]
+    fn older_pages_are_migrated_one_by_one() {
+        let v1 = json!({
+            "list": {
+                Uuid::new_v4().to_string(): { "title": "Kept", "content": r#"{"list":[{"text":"Note","is_checked":false}]}"# },
+                Uuid::new_v4().to_string(): { "title": "Broken", "content": r#"{"list":[{"te"# },
+            },
+            "current_page": null,
+        });
+        assert!(parse_state_list(&v1.to_string()).is_err());
+
+        let (state_list, lost_pages) = salvage_state_list(&v1.to_string());
+        assert_eq!(titles(&state_list), vec!["Kept"]);
+        assert_eq!(state_list.list[0].state.list[0].text, "Note");
+        assert_eq!(lost_pages, Some(1));
+
+        let v2 = json!({
+            "schema_version": 2,
+            "list": {
+                Uuid::new_v4().to_string(): { "title": "Kept", "state": { "list": [] } },
+                Uuid::new_v4().to_string(): { "title": "Broken", "state": { "list": "not a list" } },
+            },
+            "current_page": null,
+        });
+
+        let (state_list, lost_pages) = salvage_state_list(&v2.to_string());
+        assert_eq!(titles(&state_list), vec!["Kept"]);
+        assert_eq!(lost_pages, Some(1));
     }
-    Ok(AppState::default())
 }
@@ -0,0 +1,307 @@
+use std::io;
+use serde_json::{json, Map, Value};
+use uuid::Uuid;
+
+pub const STATE_LIST_VERSION: u32 = 4;
+pub const THEME_VERSION: u32 = 1;
+
+const SCHEMA_VERSION_KEY: &str = "schema_version";
+
+// Each step upgrades a document from the version matching its index to the next one.
+type Step = fn(Value) -> io::Result<Value>;
+
+const STATE_LIST_STEPS: [Step; STATE_LIST_VERSION as usize] = [
+    state_list_v0_to_v1,
+    state_list_v1_to_v2,
+    state_list_v2_to_v3,
+    state_list_v3_to_v4,
+];
+
+const THEME_STEPS: [Step; THEME_VERSION as usize] = [
+    theme_v0_to_v1,
+];
+
+pub fn migrate_state_list(value: Value) -> io::Result<Value> {
+    let version = read_version(&value).unwrap_or_else(|| unversioned_state_list_version(&value));
+    run_steps(value, version, &STATE_LIST_STEPS)
+}
+
+pub fn migrate_theme(value: Value) -> io::Result<Value> {
+    let version = read_version(&value).unwrap_or(0);
+    run_steps(value, version, &THEME_STEPS)
+}
+
+// Upgrades every page of a document on its own, for when the document as a whole can't be migrated.
+// Pages that can't be upgraded come out as null. Returns nothing if the pages can't be told apart at all.
+pub fn migrate_pages_separately(value: &Value) -> Option<Vec<Value>> {
+    let version = read_version(value);
+
+    match value.get("list")? {
+        Value::Array(pages) => Some(pages.clone()),
+        Value::Object(pages) => Some(pages.iter().map(|(key, page)| {
+            let mut document = Map::new();
+            document.insert(String::from("list"), Value::Object(Map::from_iter([(key.clone(), page.clone())])));
+            if let Some(version) = version {
+                document.insert(String::from(SCHEMA_VERSION_KEY), json!(version));
+            }
+
+            migrate_state_list(Value::Object(document)).ok()
+                .and_then(|document| document.get("list")?.get(0).cloned())
+                .unwrap_or(Value::Null)
+        }).collect()),
+        _ => None,
+    }
+}
+
+fn run_steps(mut value: Value, version: u32, steps: &[Step]) -> io::Result<Value> {
+    if version as usize > steps.len() {
+        return Err(io::Error::new(io::ErrorKind::InvalidData,
+            format!("schema version {version} was written by a newer version of the app")));
+    }
+
+    for step in &steps[version as usize..] {
+        value = step(value)?;
+    }
+    Ok(value)
+}
+
+fn read_version(value: &Value) -> Option<u32> {
+    value.get(SCHEMA_VERSION_KEY)
+        .and_then(Value::as_u64)
+        .map(|version| u32::try_from(version).unwrap_or(u32::MAX))
+}
+
+// Versions 0 and 1 predate the schema_version field, so they are told apart by their shape.
+fn unversioned_state_list_version(value: &Value) -> u32 {
+    let has_page_objects = value.get("list")
+        .and_then(Value::as_object)
+        .is_some_and(|list| list.values().any(Value::is_object));
+
+    u32::from(has_page_objects)
+}
+
+fn take_object(value: Value, what: &str) -> io::Result<Map<String, Value>> {
+    match value {
+        Value::Object(map) => Ok(map),
+        _ => Err(io::Error::new(io::ErrorKind::InvalidData, format!("expected {what} to be an object"))),
+    }
+}
+
+// v0: `{ "list": { "<title>": "<AppState as JSON string>" }, "current_app_state": "<title>" }`
+// v1: `{ "list": { "<uuid>": { "title": "<title>", "content": "<AppState as JSON string>" } }, "current_page": null }`
+fn state_list_v0_to_v1(value: Value) -> io::Result<Value> {
+    let mut root = take_object(value, "state list")?;
+    let list = take_object(root.remove("list").unwrap_or_else(|| json!({})), "page list")?;
+
+    let pages: Map<String, Value> = list.into_iter().map(|(title, content)| {
+        let page = json!({ "title": title, "content": content });
+        (Uuid::new_v4().to_string(), page)
+    }).collect();
+
+    Ok(json!({ "list": pages, "current_page": null }))
+}
+
+// v2: `{ "schema_version": 2, "list": { "<uuid>": { "title": "<title>", "state": { "list": [...] } } }, "current_page": null }`
+fn state_list_v1_to_v2(value: Value) -> io::Result<Value> {
+    let mut root = take_object(value, "state list")?;
+    let list = take_object(root.remove("list").unwrap_or_else(|| json!({})), "page list")?;
+
+    let mut pages = Map::new();
+    for (id, page) in list {
+        let mut page = take_object(page, "page")?;
+        // Newly created pages were saved with empty content, which is not valid JSON.
+        let content = page.remove("content");
+        let state = match content.as_ref().and_then(Value::as_str).filter(|content| !content.trim().is_empty()) {
+            Some(content) => serde_json::from_str(content).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?,
+            None => json!({ "list": [] }),
+        };
+
+        page.insert(String::from("state"), state);
+        pages.insert(id, Value::Object(page));
+    }
+
+    root.insert(String::from("list"), Value::Object(pages));
+    root.insert(String::from(SCHEMA_VERSION_KEY), json!(2));
+    Ok(Value::Object(root))
+}
+
+// v3: `{ "schema_version": 3, "list": [ { "id": "<uuid>", "title": "<title>", "state": {...}, "modified": "<rfc3339>" } ], ... }`
+fn state_list_v2_to_v3(value: Value) -> io::Result<Value> {
+    let mut root = take_object(value, "state list")?;
+    let list = take_object(root.remove("list").unwrap_or_else(|| json!({})), "page list")?;
+
+    let mut pages = Vec::new();
+    for (id, page) in list {
+        let mut page = take_object(page, "page")?;
+        page.insert(String::from("id"), Value::String(id));
+        pages.push(page);
+    }
+    // There was no saved order before, so start off alphabetically.
+    pages.sort_by_cached_key(|page| page.get("title").and_then(Value::as_str).map(str::to_lowercase));
+
+    root.insert(String::from("list"), Value::Array(pages.into_iter().map(Value::Object).collect()));
+    root.insert(String::from(SCHEMA_VERSION_KEY), json!(3));
+    Ok(Value::Object(root))
+}
+
+// v4: trashed notes keep their subtasks, so the note is saved as a list: `"trash": [ { "item": { "Note": [ {...} ] } } ]`
+fn state_list_v3_to_v4(value: Value) -> io::Result<Value> {
+    let mut root = take_object(value, "state list")?;
+
+    if let Some(Value::Array(trash)) = root.get_mut("trash") {
+        for item in trash {
+            if let Some(note) = item.get_mut("item").and_then(|item| item.get_mut("Note")).filter(|note| note.is_object()) {
+                *note = Value::Array(vec![note.take()]);
+            }
+        }
+    }
+
+    root.insert(String::from(SCHEMA_VERSION_KEY), json!(4));
+    Ok(Value::Object(root))
+}
+
+// v0: `{ "is_dark_mode": true }`
+fn theme_v0_to_v1(value: Value) -> io::Result<Value> {
+    let mut root = take_object(value, "theme")?;
+    root.insert(String::from(SCHEMA_VERSION_KEY), json!(1));
+    Ok(Value::Object(root))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::todo_func::{StateList, Theme, TrashedItem};
+
+    fn migrate(value: Value) -> StateList {
+        serde_json::from_value(migrate_state_list(value).unwrap()).unwrap()
+    }
+
+    // Each page's title with its notes' text and check state, in the order they come out.
+    fn summary(state_list: &StateList) -> Vec<(&str, Vec<(&str, bool)>)> {
+        state_list.list.iter().map(|page| {
+            let notes = page.state.list.iter().map(|content| (content.text.as_str(), content.is_checked)).collect();
+            (page.title.as_str(), notes)
+        }).collect()
+    }
+
+    #[test]
+    fn v0_title_keyed_json_strings() {
+        let state_list = migrate(json!({
+            "list": {
+                "Work": r#"{"list":[{"text":"Write report","is_checked":true},{"text":"Email Sam","is_checked":false}]}"#,
+                "home": r#"{"list":[{"text":"Water plants","is_checked":false}]}"#,
+            },
+            "current_app_state": "Work",
+        }));
+
+        assert_eq!(state_list.schema_version, STATE_LIST_VERSION);
+        assert_eq!(state_list.current_page, None);
+        assert_eq!(summary(&state_list), vec![
+            ("home", vec![("Water plants", false)]),
+            ("Work", vec![("Write report", true), ("Email Sam", false)]),
+        ]);
+    }
+
+    #[test]
+    fn v1_uuid_keyed_content_strings() {
+        let work = Uuid::new_v4();
+        let empty = Uuid::new_v4();
+        let state_list = migrate(json!({
+            "list": {
+                work.to_string(): { "title": "Work", "content": r#"{"list":[{"text":"Write report","is_checked":false}]}"# },
+                empty.to_string(): { "title": "Errands", "content": "" },
+            },
+            "current_page": null,
+        }));
+
+        assert_eq!(summary(&state_list), vec![
+            ("Errands", vec![]),
+            ("Work", vec![("Write report", false)]),
+        ]);
+        assert_eq!(state_list.list[0].id, empty);
+        assert_eq!(state_list.list[1].id, work);
+    }
+
+    #[test]
+    fn v2_page_object_map() {
+        let id = Uuid::new_v4();
+        let state_list = migrate(json!({
+            "schema_version": 2,
+            "list": {
+                id.to_string(): { "title": "beta", "state": { "list": [{ "text": "Ship it", "is_checked": true }] } },
+                Uuid::new_v4().to_string(): { "title": "Alpha", "state": { "list": [] } },
+            },
+            "current_page": null,
+        }));
+
+        assert_eq!(summary(&state_list), vec![
+            ("Alpha", vec![]),
+            ("beta", vec![("Ship it", true)]),
+        ]);
+        assert_eq!(state_list.list[1].id, id);
+    }
+
+    #[test]
+    fn v3_single_trashed_notes() {
+        let page = Uuid::new_v4();
+        let state_list = migrate(json!({
+            "schema_version": 3,
+            "list": [
+                { "id": page, "title": "Zeta", "state": { "list": [{ "text": "Last", "is_checked": false }] } },
+                { "id": Uuid::new_v4(), "title": "Alpha", "state": { "list": [] } },
+            ],
+            "current_page": null,
+            "trash": [{
+                "item": { "Note": { "text": "Gone", "is_checked": true } },
+                "origin_page": page,
+                "origin_title": "Zeta",
+                "deleted": "2024-05-01T12:00:00Z",
+            }],
+        }));
+
+        assert_eq!(summary(&state_list), vec![
+            ("Zeta", vec![("Last", false)]),
+            ("Alpha", vec![]),
+        ]);
+        let TrashedItem::Note(notes) = &state_list.trash[0].item else {
+            panic!("expected a trashed note");
+        };
+        assert_eq!(notes.len(), 1);
+        assert_eq!(notes[0].text, "Gone");
+    }
+
+    #[test]
+    fn v4_is_left_as_saved() {
+        let state_list = migrate(json!({
+            "schema_version": 4,
+            "list": [
+                { "id": Uuid::new_v4(), "title": "Zeta", "state": { "list": [] } },
+                { "id": Uuid::new_v4(), "title": "Alpha", "state": { "list": [{ "text": "First", "is_checked": false }] } },
+            ],
+            "current_page": null,
+        }));
+
+        assert_eq!(summary(&state_list), vec![
+            ("Zeta", vec![]),
+            ("Alpha", vec![("First", false)]),
+        ]);
+    }
+
+    #[test]
+    fn newer_versions_are_refused() {
+        let error = migrate_state_list(json!({ "schema_version": STATE_LIST_VERSION + 1, "list": [] })).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+
+        let error = run_steps(json!({}), 2, &THEME_STEPS).unwrap_err();
+        assert!(error.to_string().contains("newer version"));
+    }
+
+    #[test]
+    fn theme_v0_gets_a_version() {
+        let theme: Theme = serde_json::from_value(migrate_theme(json!({ "is_dark_mode": true })).unwrap()).unwrap();
+        assert_eq!(theme.schema_version, THEME_VERSION);
+        assert!(theme.is_dark_mode);
+
+        assert!(migrate_theme(json!({ "schema_version": THEME_VERSION + 1, "is_dark_mode": true })).is_err());
+    }
+}
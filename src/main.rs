@@ -4,6 +4,13 @@ use todo_func::TodoApp;
 
 mod todo_func;
 mod json_parser;
+mod history;
+mod shortcuts;
+mod recurrence;
+mod reminders;
+mod duplicates;
+#[cfg(test)]
+mod test_support;
 
 mod ui {
     pub mod center_panel;
@@ -11,6 +18,13 @@ mod ui {
     pub mod top_panel;
     pub mod settings;
     pub mod popups;
+    pub mod trash;
+    pub mod command_palette;
+    pub mod due_date;
+    pub mod tags;
+    pub mod search;
+    pub mod bulk_actions;
+    pub mod duplicates;
 }
 
 const HEADER_TO_BODY_PADDING: f32 = 14.0;
@@ -0,0 +1,170 @@
+use std::collections::HashMap;
+
+use chrono::{Datelike, Days, Months, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+
+// How often a note comes back after it is checked. Saved as an iCalendar RRULE so the rules stay readable
+// and can be copied from other apps, though only the forms below are understood.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(try_from = "String", into = "String")]
+pub enum Recurrence {
+    EveryDays(u32),
+    Weekdays,
+    EveryWeeks(u32),
+    // Months without that day repeat on their last day instead.
+    MonthlyOnDay(u32),
+}
+
+impl Recurrence {
+    pub fn label(self) -> String {
+        match self {
+            Self::EveryDays(1) => String::from("Daily"),
+            Self::EveryDays(days) => format!("Every {days} days"),
+            Self::Weekdays => String::from("Weekdays"),
+            Self::EveryWeeks(1) => String::from("Weekly"),
+            Self::EveryWeeks(weeks) => format!("Every {weeks} weeks"),
+            Self::MonthlyOnDay(day) => format!("Monthly on day {day}"),
+        }
+    }
+
+    // The first date after `date` the note is due again.
+    pub fn next(self, date: NaiveDate) -> NaiveDate {
+        match self {
+            Self::EveryDays(days) => date + Days::new(u64::from(days)),
+            Self::Weekdays => {
+                let mut next = date + Days::new(1);
+                while matches!(next.weekday(), Weekday::Sat | Weekday::Sun) {
+                    next = next + Days::new(1);
+                }
+                next
+            },
+            Self::EveryWeeks(weeks) => date + Days::new(u64::from(weeks) * 7),
+            Self::MonthlyOnDay(day) => {
+                let first_of_month = date.with_day(1).unwrap_or(date);
+                (0..=1).filter_map(|months| first_of_month.checked_add_months(Months::new(months)))
+                    .filter_map(|month| month.with_day(day.min(u32::from(month.num_days_in_month()))))
+                    .find(|candidate| *candidate > date)
+                    .unwrap_or(date)
+            },
+        }
+    }
+
+    pub fn to_rrule(self) -> String {
+        match self {
+            Self::EveryDays(days) => format!("FREQ=DAILY;INTERVAL={days}"),
+            Self::Weekdays => String::from("FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR"),
+            Self::EveryWeeks(weeks) => format!("FREQ=WEEKLY;INTERVAL={weeks}"),
+            Self::MonthlyOnDay(day) => format!("FREQ=MONTHLY;BYMONTHDAY={day}"),
+        }
+    }
+
+    pub fn from_rrule(rule: &str) -> Result<Self, String> {
+        let rule = rule.trim();
+        let rule = rule.strip_prefix("RRULE:").unwrap_or(rule);
+
+        let mut parts = HashMap::new();
+        for part in rule.split(';').filter(|part| !part.is_empty()) {
+            let (key, value) = part.split_once('=').ok_or_else(|| format!("\"{part}\" is not a KEY=VALUE pair"))?;
+            parts.insert(key.trim().to_uppercase(), value.trim().to_uppercase());
+        }
+
+        let number = |key: &str, default: u32| -> Result<u32, String> {
+            parts.get(key).map_or(Ok(default), |value| {
+                value.parse().ok().filter(|number| *number > 0).ok_or_else(|| format!("{key} must be a positive number"))
+            })
+        };
+        let interval = number("INTERVAL", 1)?;
+
+        let recurrence = match parts.get("FREQ").map(String::as_str) {
+            Some("DAILY") => Self::EveryDays(interval),
+            Some("WEEKLY") => match parts.get("BYDAY").map(String::as_str) {
+                None => Self::EveryWeeks(interval),
+                Some("MO,TU,WE,TH,FR") if interval == 1 => Self::Weekdays,
+                Some(_) => return Err(String::from("Only BYDAY=MO,TU,WE,TH,FR is supported")),
+            },
+            Some("MONTHLY") if interval == 1 => match number("BYMONTHDAY", 0)? {
+                day @ 1..=31 => Self::MonthlyOnDay(day),
+                _ => return Err(String::from("BYMONTHDAY must be between 1 and 31")),
+            },
+            Some("MONTHLY") => return Err(String::from("Monthly rules can't have an INTERVAL")),
+            Some(freq) => return Err(format!("FREQ={freq} is not supported")),
+            None => return Err(String::from("FREQ is missing")),
+        };
+
+        Ok(recurrence)
+    }
+}
+
+impl TryFrom<String> for Recurrence {
+    type Error = String;
+
+    fn try_from(rule: String) -> Result<Self, Self::Error> {
+        Self::from_rrule(&rule)
+    }
+}
+
+impl From<Recurrence> for String {
+    fn from(recurrence: Recurrence) -> Self {
+        recurrence.to_rrule()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::todo_func::{Content, Due};
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn next_occurrences() {
+        assert_eq!(Recurrence::EveryDays(3).next(date(2024, 5, 30)), date(2024, 6, 2));
+        assert_eq!(Recurrence::EveryWeeks(2).next(date(2024, 5, 1)), date(2024, 5, 15));
+        // Friday to Monday.
+        assert_eq!(Recurrence::Weekdays.next(date(2024, 5, 3)), date(2024, 5, 6));
+        assert_eq!(Recurrence::Weekdays.next(date(2024, 5, 6)), date(2024, 5, 7));
+    }
+
+    #[test]
+    fn monthly_falls_back_to_the_last_day() {
+        let monthly = Recurrence::MonthlyOnDay(31);
+        assert_eq!(monthly.next(date(2024, 1, 31)), date(2024, 2, 29));
+        assert_eq!(monthly.next(date(2024, 2, 29)), date(2024, 3, 31));
+        assert_eq!(monthly.next(date(2024, 4, 10)), date(2024, 4, 30));
+        assert_eq!(Recurrence::MonthlyOnDay(15).next(date(2024, 12, 15)), date(2025, 1, 15));
+    }
+
+    #[test]
+    fn rrules_round_trip() {
+        for recurrence in [Recurrence::EveryDays(1), Recurrence::EveryDays(4), Recurrence::Weekdays,
+            Recurrence::EveryWeeks(2), Recurrence::MonthlyOnDay(31)] {
+            assert_eq!(Recurrence::from_rrule(&recurrence.to_rrule()), Ok(recurrence));
+        }
+        assert_eq!(Recurrence::from_rrule("RRULE:freq=weekly; interval=3"), Ok(Recurrence::EveryWeeks(3)));
+        assert_eq!(Recurrence::from_rrule("FREQ=DAILY"), Ok(Recurrence::EveryDays(1)));
+    }
+
+    #[test]
+    fn unsupported_rrules_are_refused() {
+        for rule in ["", "INTERVAL=2", "FREQ=YEARLY", "FREQ=DAILY;INTERVAL=0", "FREQ=WEEKLY;BYDAY=SA,SU",
+            "FREQ=MONTHLY;INTERVAL=2;BYMONTHDAY=1", "FREQ=MONTHLY;BYMONTHDAY=32", "FREQ"] {
+            assert!(Recurrence::from_rrule(rule).is_err(), "{rule} should be refused");
+        }
+    }
+
+    #[test]
+    fn rescheduling_skips_past_occurrences() {
+        let mut content = Content::new(String::from("Water plants"));
+        content.recurrence = Some(Recurrence::EveryDays(2));
+        content.due = Some(Due { date: date(2024, 5, 1), time: None });
+        content.is_checked = true;
+
+        assert!(content.reschedule(date(2024, 5, 6)));
+        assert_eq!(content.due.map(|due| due.date), Some(date(2024, 5, 7)));
+        assert!(!content.is_checked);
+
+        assert!(!Content::new(String::from("Once")).reschedule(date(2024, 5, 6)));
+    }
+}
@@ -0,0 +1,277 @@
+use std::process::Command;
+use std::time::Duration;
+
+use chrono::{Local, NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::todo_func::{Content, StateList, TodoApp};
+
+// Notes that are due on a date without a time are reminded about at this hour.
+const DEFAULT_REMINDER_HOUR: u32 = 9;
+// Upper bound on how long the app sleeps between checks, so changes to the system clock are noticed.
+const MAX_POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Reminder {
+    pub minutes_before: u32,
+    #[serde(default)]
+    pub snoozed_until: Option<NaiveDateTime>,
+    // The due time the reminder was last closed for, so it comes back once the note is due again.
+    #[serde(default)]
+    pub dismissed_for: Option<NaiveDateTime>,
+}
+
+impl Content {
+    fn reminder_due_time(&self) -> Option<NaiveDateTime> {
+        let default_time = NaiveTime::from_hms_opt(DEFAULT_REMINDER_HOUR, 0, 0).unwrap_or(NaiveTime::MIN);
+        self.due.map(|due| due.date.and_time(due.time.unwrap_or(default_time)))
+    }
+
+    // The time the note's reminder is due, or nothing if it has none or it was already dealt with.
+    pub fn reminder_time(&self) -> Option<NaiveDateTime> {
+        let reminder = self.reminder.filter(|_| !self.is_checked)?;
+        let due_time = self.reminder_due_time()?;
+
+        if reminder.dismissed_for == Some(due_time) {
+            return None;
+        }
+
+        Some(reminder.snoozed_until.unwrap_or_else(|| due_time - chrono::Duration::minutes(i64::from(reminder.minutes_before))))
+    }
+
+    pub fn snooze_reminder(&mut self, until: NaiveDateTime) {
+        if let Some(reminder) = &mut self.reminder {
+            reminder.snoozed_until = Some(until);
+        }
+    }
+
+    pub fn dismiss_reminder(&mut self) {
+        let due_time = self.reminder_due_time();
+        if let Some(reminder) = &mut self.reminder {
+            reminder.snoozed_until = None;
+            reminder.dismissed_for = due_time;
+        }
+    }
+}
+
+// Where reminders go besides the in-app popup. Kept behind a trait so the scheduler can be driven with a fake.
+pub trait Notifier {
+    fn notify(&mut self, title: &str, body: &str);
+}
+
+// Hands the reminder to the operating system's own notification tool. Nothing happens if the tool is missing,
+// the popup inside the app is shown either way.
+pub struct DesktopNotifier;
+
+impl Notifier for DesktopNotifier {
+    fn notify(&mut self, title: &str, body: &str) {
+        let mut command = if cfg!(target_os = "windows") {
+            let script = format!(
+                "Add-Type -AssemblyName System.Windows.Forms; $n = New-Object System.Windows.Forms.NotifyIcon; \
+                 $n.Icon = [System.Drawing.SystemIcons]::Information; $n.Visible = $true; \
+                 $n.ShowBalloonTip(10000, '{}', '{}', 'Info'); Start-Sleep 10; $n.Dispose()",
+                title.replace('\'', "''"), body.replace('\'', "''"));
+            let mut command = Command::new("powershell");
+            command.args(["-NoProfile", "-Command", &script]);
+            command
+        } else if cfg!(target_os = "macos") {
+            let script = format!("display notification {body:?} with title {title:?}");
+            let mut command = Command::new("osascript");
+            command.args(["-e", &script]);
+            command
+        } else {
+            let mut command = Command::new("notify-send");
+            command.args([title, body]);
+            command
+        };
+
+        // Keeps powershell from opening a console window next to the app.
+        #[cfg(windows)]
+        {
+            use std::os::windows::process::CommandExt;
+            const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+            command.creation_flags(CREATE_NO_WINDOW);
+        }
+
+        // Waited on from its own thread, so the finished process doesn't linger as a zombie.
+        if let Ok(mut child) = command.spawn() {
+            std::thread::spawn(move || child.wait());
+        }
+    }
+}
+
+// A reminder that came due and is waiting for the user to snooze or close it.
+pub struct FiredReminder {
+    pub page: Uuid,
+    pub note: Uuid,
+    // Came due while the app was closed.
+    pub missed: bool,
+}
+
+pub struct ReminderScheduler {
+    notifier: Box<dyn Notifier>,
+    pub active: Vec<FiredReminder>,
+    started: NaiveDateTime,
+}
+
+impl Default for ReminderScheduler {
+    fn default() -> Self {
+        Self::with_notifier(Box::new(DesktopNotifier))
+    }
+}
+
+impl ReminderScheduler {
+    pub fn with_notifier(notifier: Box<dyn Notifier>) -> Self {
+        Self { notifier, active: Vec::new(), started: Local::now().naive_local() }
+    }
+
+    // Picks up every reminder that came due, including the ones missed while the app was closed, drops the
+    // ones that no longer apply and returns when the next one is due.
+    pub fn poll(&mut self, state_list: &StateList, now: NaiveDateTime, notify: bool) -> Option<NaiveDateTime> {
+        let mut next = None;
+        let mut still_due = Vec::new();
+
+        for page in &state_list.list {
+            for content in &page.state.list {
+                let Some(time) = content.reminder_time() else {
+                    continue;
+                };
+
+                if time > now {
+                    next = Some(next.map_or(time, |next: NaiveDateTime| next.min(time)));
+                    continue;
+                }
+
+                still_due.push(content.id);
+                if self.active.iter().any(|fired| fired.note == content.id) {
+                    continue;
+                }
+
+                if notify {
+                    self.notifier.notify("⏰ Reminder", &content.text);
+                }
+                self.active.push(FiredReminder { page: page.id, note: content.id, missed: time < self.started });
+            }
+        }
+
+        self.active.retain(|fired| still_due.contains(&fired.note));
+        next
+    }
+}
+
+impl TodoApp {
+    // egui only redraws on input, so a repaint is requested for when the next reminder is due.
+    pub fn check_reminders(&mut self, ctx: &eframe::egui::Context) {
+        let now = Local::now().naive_local();
+        let next = self.reminders.poll(&self.state_list, now, self.dark_mode.desktop_notifications);
+
+        let wait = next.and_then(|next| (next - now).to_std().ok()).map_or(MAX_POLL_INTERVAL, |wait| wait.min(MAX_POLL_INTERVAL));
+        ctx.request_repaint_after(wait);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use chrono::NaiveDate;
+
+    use super::*;
+    use crate::todo_func::{Due, Page};
+
+    // Keeps every notification it is given instead of showing it.
+    #[derive(Clone, Default)]
+    struct RecordingNotifier(Rc<RefCell<Vec<String>>>);
+
+    impl Notifier for RecordingNotifier {
+        fn notify(&mut self, _title: &str, body: &str) {
+            self.0.borrow_mut().push(body.to_owned());
+        }
+    }
+
+    fn at(day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2024, 5, day).unwrap().and_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    fn reminded_note(text: &str, due: NaiveDateTime) -> Content {
+        let mut content = Content::new(String::from(text));
+        content.due = Some(Due { date: due.date(), time: Some(due.time()) });
+        content.reminder = Some(Reminder::default());
+        content
+    }
+
+    fn state_list(notes: Vec<Content>) -> StateList {
+        let mut page = Page::new(String::from("Work"));
+        page.state.list = notes;
+        StateList { list: vec![page], ..StateList::default() }
+    }
+
+    fn scheduler(started: NaiveDateTime) -> (ReminderScheduler, RecordingNotifier) {
+        let notifier = RecordingNotifier::default();
+        let mut scheduler = ReminderScheduler::with_notifier(Box::new(notifier.clone()));
+        scheduler.started = started;
+        (scheduler, notifier)
+    }
+
+    #[test]
+    fn reminders_from_while_the_app_was_closed_are_caught_up() {
+        let state_list = state_list(vec![
+            reminded_note("Yesterday", at(1, 10, 0)),
+            reminded_note("Just now", at(2, 11, 30)),
+            reminded_note("Later", at(2, 15, 0)),
+        ]);
+        let (mut scheduler, notifier) = scheduler(at(2, 11, 0));
+
+        let next = scheduler.poll(&state_list, at(2, 12, 0), true);
+
+        let missed: Vec<bool> = scheduler.active.iter().map(|fired| fired.missed).collect();
+        assert_eq!(missed, vec![true, false]);
+        assert_eq!(*notifier.0.borrow(), vec!["Yesterday", "Just now"]);
+        assert_eq!(next, Some(at(2, 15, 0)));
+    }
+
+    #[test]
+    fn active_reminders_are_not_sent_again() {
+        let state_list = state_list(vec![reminded_note("Call back", at(2, 11, 0))]);
+        let (mut scheduler, notifier) = scheduler(at(2, 9, 0));
+
+        scheduler.poll(&state_list, at(2, 12, 0), true);
+        scheduler.poll(&state_list, at(2, 12, 1), true);
+
+        assert_eq!(scheduler.active.len(), 1);
+        assert_eq!(notifier.0.borrow().len(), 1);
+    }
+
+    #[test]
+    fn snoozed_and_dismissed_reminders_are_dropped() {
+        let mut state_list = state_list(vec![reminded_note("Call back", at(2, 11, 0))]);
+        let (mut scheduler, _) = scheduler(at(2, 9, 0));
+        scheduler.poll(&state_list, at(2, 12, 0), true);
+
+        state_list.list[0].state.list[0].snooze_reminder(at(2, 12, 10));
+        let next = scheduler.poll(&state_list, at(2, 12, 1), true);
+        assert!(scheduler.active.is_empty());
+        assert_eq!(next, Some(at(2, 12, 10)));
+
+        scheduler.poll(&state_list, at(2, 12, 10), true);
+        assert_eq!(scheduler.active.len(), 1);
+
+        state_list.list[0].state.list[0].dismiss_reminder();
+        let next = scheduler.poll(&state_list, at(2, 12, 11), true);
+        assert!(scheduler.active.is_empty());
+        assert_eq!(next, None);
+    }
+
+    #[test]
+    fn nothing_is_sent_with_notifications_off() {
+        let state_list = state_list(vec![reminded_note("Call back", at(2, 11, 0))]);
+        let (mut scheduler, notifier) = scheduler(at(2, 9, 0));
+
+        scheduler.poll(&state_list, at(2, 12, 0), false);
+
+        assert_eq!(scheduler.active.len(), 1);
+        assert!(notifier.0.borrow().is_empty());
+    }
+}
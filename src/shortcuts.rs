@@ -0,0 +1,455 @@
+use std::collections::HashMap;
+
+use eframe::egui::{Event, Id, Key, KeyboardShortcut, Modifiers, RawInput};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::todo_func::{NoteSort, TodoApp};
+
+const FOCUS_MOVED_ID_NAME: &str = "note_focus_moved";
+const KEYMAP_WARNING_ID_NAME: &str = "keymap_warning_message";
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Action {
+    NewNote,
+    NewPage,
+    ToggleSidePanel,
+    OpenSettings,
+    RenamePage,
+    Undo,
+    Redo,
+    NextNote,
+    PreviousNote,
+    ToggleNote,
+    DeleteNote,
+    RaisePriority,
+    LowerPriority,
+    IndentNote,
+    OutdentNote,
+    MoveNoteUp,
+    MoveNoteDown,
+    SelectAll,
+    ShowShortcuts,
+    OpenPalette,
+    OpenCommandPalette,
+    Search,
+    ToggleTheme,
+    OpenTrash,
+    ClearData,
+}
+
+impl Action {
+    pub const ALL: [Self; 25] = [
+        Self::NewNote,
+        Self::NewPage,
+        Self::ToggleSidePanel,
+        Self::OpenSettings,
+        Self::RenamePage,
+        Self::Undo,
+        Self::Redo,
+        Self::NextNote,
+        Self::PreviousNote,
+        Self::ToggleNote,
+        Self::DeleteNote,
+        Self::RaisePriority,
+        Self::LowerPriority,
+        Self::IndentNote,
+        Self::OutdentNote,
+        Self::MoveNoteUp,
+        Self::MoveNoteDown,
+        Self::SelectAll,
+        Self::ShowShortcuts,
+        Self::OpenPalette,
+        Self::OpenCommandPalette,
+        Self::Search,
+        Self::ToggleTheme,
+        Self::OpenTrash,
+        Self::ClearData,
+    ];
+
+    pub const fn description(self) -> &'static str {
+        match self {
+            Self::NewNote => "New note",
+            Self::NewPage => "New page",
+            Self::ToggleSidePanel => "Show/hide pages",
+            Self::OpenSettings => "Open settings",
+            Self::RenamePage => "Rename current page",
+            Self::Undo => "Undo",
+            Self::Redo => "Redo",
+            Self::NextNote => "Focus next note",
+            Self::PreviousNote => "Focus previous note",
+            Self::ToggleNote => "Check/uncheck focused note",
+            Self::DeleteNote => "Delete focused note",
+            Self::RaisePriority => "Raise priority of focused note",
+            Self::LowerPriority => "Lower priority of focused note",
+            Self::IndentNote => "Make focused note a subtask",
+            Self::OutdentNote => "Move focused note out a level",
+            Self::MoveNoteUp => "Move focused note up",
+            Self::MoveNoteDown => "Move focused note down",
+            Self::SelectAll => "Select all shown notes",
+            Self::ShowShortcuts => "Show keyboard shortcuts",
+            Self::OpenPalette => "Go to page or command",
+            Self::OpenCommandPalette => "Show all commands",
+            Self::Search => "Search all pages",
+            Self::ToggleTheme => "Toggle light/dark theme",
+            Self::OpenTrash => "Open trash",
+            Self::ClearData => "Reset all data",
+        }
+    }
+
+    // Actions that are rarely needed or destructive are left unbound and reached through the command palette.
+    pub const fn default_shortcut(self) -> Option<KeyboardShortcut> {
+        let ctrl_shift = Modifiers::COMMAND.plus(Modifiers::SHIFT);
+        let shortcut = match self {
+            Self::NewNote => KeyboardShortcut::new(Modifiers::COMMAND, Key::N),
+            Self::NewPage => KeyboardShortcut::new(ctrl_shift, Key::N),
+            Self::ToggleSidePanel => KeyboardShortcut::new(Modifiers::COMMAND, Key::B),
+            Self::OpenSettings => KeyboardShortcut::new(Modifiers::COMMAND, Key::Comma),
+            Self::RenamePage => KeyboardShortcut::new(Modifiers::NONE, Key::F2),
+            Self::Undo => KeyboardShortcut::new(Modifiers::COMMAND, Key::Z),
+            Self::Redo => KeyboardShortcut::new(ctrl_shift, Key::Z),
+            Self::NextNote => KeyboardShortcut::new(Modifiers::NONE, Key::ArrowDown),
+            Self::PreviousNote => KeyboardShortcut::new(Modifiers::NONE, Key::ArrowUp),
+            Self::ToggleNote => KeyboardShortcut::new(Modifiers::NONE, Key::Space),
+            Self::DeleteNote => KeyboardShortcut::new(Modifiers::NONE, Key::Delete),
+            Self::RaisePriority => KeyboardShortcut::new(Modifiers::COMMAND, Key::CloseBracket),
+            Self::LowerPriority => KeyboardShortcut::new(Modifiers::COMMAND, Key::OpenBracket),
+            Self::IndentNote => KeyboardShortcut::new(Modifiers::NONE, Key::Tab),
+            Self::OutdentNote => KeyboardShortcut::new(Modifiers::SHIFT, Key::Tab),
+            Self::MoveNoteUp => KeyboardShortcut::new(Modifiers::ALT, Key::ArrowUp),
+            Self::MoveNoteDown => KeyboardShortcut::new(Modifiers::ALT, Key::ArrowDown),
+            Self::SelectAll => KeyboardShortcut::new(Modifiers::COMMAND, Key::A),
+            Self::ShowShortcuts => KeyboardShortcut::new(Modifiers::NONE, Key::F1),
+            Self::OpenPalette => KeyboardShortcut::new(Modifiers::COMMAND, Key::P),
+            Self::OpenCommandPalette => KeyboardShortcut::new(ctrl_shift, Key::P),
+            Self::Search => KeyboardShortcut::new(Modifiers::COMMAND, Key::F),
+            Self::ToggleTheme | Self::OpenTrash | Self::ClearData => return None,
+        };
+        Some(shortcut)
+    }
+
+    // The rest would steal keys (and text field undo) from whoever is typing.
+    const fn works_while_typing(self) -> bool {
+        !matches!(self, Self::RenamePage | Self::Undo | Self::Redo | Self::NextNote | Self::PreviousNote | Self::ToggleNote | Self::DeleteNote
+            | Self::RaisePriority | Self::LowerPriority | Self::IndentNote | Self::OutdentNote
+            | Self::MoveNoteUp | Self::MoveNoteDown | Self::SelectAll)
+    }
+
+    // Actions that only do something to the focused note, and whose keys egui needs otherwise.
+    pub const fn needs_focused_note(self) -> bool {
+        matches!(self, Self::ToggleNote | Self::DeleteNote | Self::RaisePriority | Self::LowerPriority
+            | Self::IndentNote | Self::OutdentNote | Self::MoveNoteUp | Self::MoveNoteDown)
+    }
+}
+
+// Key chords picked by the user. Actions without an entry keep their default shortcut.
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct Keymap {
+    bindings: HashMap<Action, KeyboardShortcut>,
+}
+
+impl Keymap {
+    pub fn shortcut(&self, action: Action) -> Option<KeyboardShortcut> {
+        self.bindings.get(&action).copied().or_else(|| action.default_shortcut())
+    }
+
+    // Empty for actions without a binding, so it can be passed straight to `Button::shortcut_text`.
+    pub fn shortcut_text(&self, ctx: &eframe::egui::Context, action: Action) -> String {
+        self.shortcut(action).map(|shortcut| ctx.format_shortcut(&shortcut)).unwrap_or_default()
+    }
+
+    // Returns the action that already uses the chord, if it is not the one being rebound.
+    pub fn conflict(&self, action: Action, shortcut: KeyboardShortcut) -> Option<Action> {
+        Action::ALL.into_iter().find(|other| *other != action && self.shortcut(*other) == Some(shortcut))
+    }
+
+    pub fn bind(&mut self, action: Action, shortcut: KeyboardShortcut) {
+        if Some(shortcut) == action.default_shortcut() {
+            self.bindings.remove(&action);
+        } else {
+            self.bindings.insert(action, shortcut);
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.bindings.clear();
+    }
+
+    pub fn is_default(&self) -> bool {
+        self.bindings.is_empty()
+    }
+}
+
+// Chords without Ctrl/Alt would type into the focused text field instead, unless they are function keys.
+const fn is_safe_while_typing(shortcut: KeyboardShortcut) -> bool {
+    let modifiers = shortcut.modifiers;
+    modifiers.command || modifiers.ctrl || modifiers.alt || modifiers.mac_cmd || matches!(shortcut.logical_key,
+        Key::F1 | Key::F2 | Key::F3 | Key::F4 | Key::F5 | Key::F6 | Key::F7 | Key::F8 | Key::F9 | Key::F10 | Key::F11 | Key::F12)
+}
+
+// Removes every key press bound to an action that can run right now from the input, so egui never sees it,
+// and returns the actions. Key presses for actions that can't run are left to egui.
+pub fn take_actions(raw_input: &mut RawInput, typing: bool, keymap: &Keymap, can_run: impl Fn(Action) -> bool) -> Vec<Action> {
+    let mut actions = Vec::new();
+
+    raw_input.events.retain(|event| {
+        let Event::Key { key, pressed: true, modifiers, .. } = event else {
+            return true;
+        };
+
+        let action = Action::ALL.into_iter().find(|action| {
+            let Some(shortcut) = keymap.shortcut(*action) else {
+                return false;
+            };
+            shortcut.logical_key == *key
+                && modifiers.matches_exact(shortcut.modifiers)
+                && (!typing || (action.works_while_typing() && is_safe_while_typing(shortcut)))
+                && can_run(*action)
+        });
+
+        if let Some(action) = action {
+            actions.push(action);
+            return false;
+        }
+        true
+    });
+
+    actions
+}
+
+// Takes the first key press out of the input, for when the user is choosing a new chord.
+pub fn take_chord(raw_input: &mut RawInput) -> Option<KeyboardShortcut> {
+    let index = raw_input.events.iter().position(|event| matches!(event, Event::Key { pressed: true, .. }))?;
+    match raw_input.events.remove(index) {
+        Event::Key { key, modifiers, .. } => Some(KeyboardShortcut::new(modifiers, key)),
+        _ => None,
+    }
+}
+
+impl TodoApp {
+    pub fn start_rebinding(&mut self, ctx: &eframe::egui::Context, action: Action) {
+        self.rebinding_action = Some(action);
+        Self::write_temp_mem(ctx, KEYMAP_WARNING_ID_NAME, "");
+    }
+
+    // Escape cancels, and a chord that another action already uses is refused with a warning.
+    pub fn finish_rebinding(&mut self, ctx: &eframe::egui::Context, shortcut: KeyboardShortcut) {
+        let Some(action) = self.rebinding_action.take() else {
+            return;
+        };
+
+        if shortcut.logical_key == Key::Escape && shortcut.modifiers.is_none() {
+            return;
+        }
+
+        if let Some(other) = self.dark_mode.keymap.conflict(action, shortcut) {
+            let warning = format!("⚠ {} is already used by \"{}\". ⚠", ctx.format_shortcut(&shortcut), other.description());
+            Self::write_temp_mem(ctx, KEYMAP_WARNING_ID_NAME, &warning);
+            return;
+        }
+
+        self.dark_mode.keymap.bind(action, shortcut);
+    }
+
+    pub fn read_keymap_warning(ctx: &eframe::egui::Context) -> String {
+        Self::read_temp_mem(ctx, KEYMAP_WARNING_ID_NAME).unwrap_or_default()
+    }
+
+    pub fn run_action(&mut self, ctx: &eframe::egui::Context, action: Action) {
+        match action {
+            Action::NewNote => self.open_add_panel(ctx),
+            Action::NewPage => {
+                self.panel_manager.show_side_panel(true);
+                self.open_add_page_panel(ctx);
+            },
+            Action::ToggleSidePanel => self.panel_manager.show_side_panel(!self.panel_manager.side_panel_visible),
+            Action::OpenSettings => self.panel_manager.show_settings(true),
+            Action::RenamePage => {
+                if let Some(id) = self.state_list.current_page {
+                    self.panel_manager.show_side_panel(true);
+                    self.start_renaming_page(ctx, id);
+                }
+            },
+            Action::Undo => self.undo(),
+            Action::Redo => self.redo(),
+            Action::NextNote => self.move_note_focus(ctx, 1),
+            Action::PreviousNote => self.move_note_focus(ctx, -1),
+            Action::ToggleNote => self.toggle_focused_note(),
+            Action::DeleteNote => self.delete_focused_note(),
+            Action::RaisePriority => self.step_focused_priority(1),
+            Action::LowerPriority => self.step_focused_priority(-1),
+            Action::IndentNote => self.indent_focused_note(true),
+            Action::OutdentNote => self.indent_focused_note(false),
+            Action::MoveNoteUp => self.move_focused_note(ctx, true),
+            Action::MoveNoteDown => self.move_focused_note(ctx, false),
+            Action::SelectAll => self.select_all_notes(),
+            Action::ShowShortcuts => self.panel_manager.show_shortcuts(!self.panel_manager.shortcuts_visible),
+            Action::OpenPalette => self.open_command_palette(ctx, false),
+            Action::OpenCommandPalette => self.open_command_palette(ctx, true),
+            Action::Search => self.open_search(ctx),
+            Action::ToggleTheme => {
+                self.dark_mode.is_dark_mode = !self.dark_mode.is_dark_mode;
+                self.update_theme(ctx);
+            },
+            Action::OpenTrash => self.panel_manager.show_trash(true),
+            Action::ClearData => self.panel_manager.show_reset_popup(true),
+        }
+    }
+
+    fn focused_note_index(&self) -> Option<usize> {
+        self.focused_note.and_then(|id| self.state.list.iter().position(|content| content.id == id))
+    }
+
+    // Follows the order the notes are shown in, skipping the ones hidden by the page's view.
+    fn move_note_focus(&mut self, ctx: &eframe::egui::Context, step: isize) {
+        let visible = self.state.visible_indices();
+        let Some(last) = visible.len().checked_sub(1) else {
+            return;
+        };
+
+        let position = self.focused_note_index().and_then(|index| visible.iter().position(|i| *i == index));
+        let position = match position {
+            Some(position) => position.saturating_add_signed(step).min(last),
+            None if step > 0 => 0,
+            None => last,
+        };
+
+        self.focused_note = Some(self.state.list[visible[position]].id);
+        Self::write_persist_state(ctx, FOCUS_MOVED_ID_NAME, true);
+    }
+
+    // Opens the page the note is on, makes sure the note is shown and moves the focus to it with a short flash.
+    pub fn go_to_note(&mut self, ctx: &eframe::egui::Context, page: Uuid, note: Uuid) {
+        if !self.is_current_page(page) {
+            self.state_list.current_page = Some(page);
+            self.show_updated_state();
+        }
+
+        if self.state.index_of(note).is_none() {
+            return;
+        }
+        self.state.reveal(note);
+
+        self.focused_note = Some(note);
+        self.start_note_flash(ctx, note);
+        Self::write_persist_state(ctx, FOCUS_MOVED_ID_NAME, true);
+    }
+
+    // Returns true only once after the focus moved, so the note gets scrolled into view a single time.
+    pub fn take_note_focus_moved(ctx: &eframe::egui::Context) -> bool {
+        let moved = Self::read_persist_state(ctx, FOCUS_MOVED_ID_NAME).unwrap_or_default();
+        if moved {
+            ctx.memory_mut(|mem| mem.data.remove::<bool>(Id::new(FOCUS_MOVED_ID_NAME)));
+        }
+        moved
+    }
+
+    fn toggle_focused_note(&mut self) {
+        let Some(index) = self.focused_note_index() else {
+            return;
+        };
+
+        let checked = !self.state.list[index].is_checked;
+        let label = self.state.set_checked(index, checked, self.dark_mode.auto_check_parents);
+        self.record_history(label);
+        self.update_state();
+    }
+
+    pub fn indent_focused_note(&mut self, indent: bool) {
+        let Some(index) = self.focused_note_index() else {
+            return;
+        };
+
+        let changed = if indent { self.state.indent(index) } else { self.state.outdent(index) };
+        if changed {
+            self.record_history(if indent {"Indent note"} else {"Outdent note"});
+            self.update_state();
+        }
+    }
+
+    fn step_focused_priority(&mut self, step: isize) {
+        let Some(index) = self.focused_note_index() else {
+            return;
+        };
+
+        let content = &mut self.state.list[index];
+        let priority = content.priority.step(step);
+        if priority == content.priority {
+            return;
+        }
+        content.priority = priority;
+        self.record_history("Set priority");
+        self.update_state();
+    }
+
+    // Only works in manual order, since any other sort would put the note straight back.
+    fn move_focused_note(&mut self, ctx: &eframe::egui::Context, up: bool) {
+        if self.state.view.sort != NoteSort::Manual {
+            return;
+        }
+        let Some(index) = self.focused_note_index() else {
+            return;
+        };
+
+        if self.state.move_among_siblings(index, up).is_some() {
+            self.record_history("Move note");
+            self.update_state();
+            Self::write_persist_state(ctx, FOCUS_MOVED_ID_NAME, true);
+        }
+    }
+
+    fn delete_focused_note(&mut self) {
+        let Some(index) = self.focused_note_index() else {
+            return;
+        };
+        let position = self.state.visible_indices().iter().position(|i| *i == index).unwrap_or_default();
+
+        self.record_history("Delete note");
+        self.delete_content(&[index]);
+        self.update_state();
+
+        // Keep the focus in place so several notes can be deleted in a row.
+        let visible = self.state.visible_indices();
+        let next = position.min(visible.len().saturating_sub(1));
+        self.focused_note = visible.get(next).map(|i| self.state.list[*i].id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key_press(key: Key, modifiers: Modifiers) -> RawInput {
+        let event = Event::Key { key, physical_key: None, pressed: true, repeat: false, modifiers };
+        RawInput { events: vec![event], ..RawInput::default() }
+    }
+
+    #[test]
+    fn keys_for_unavailable_actions_are_left_to_egui() {
+        let keymap = Keymap::default();
+        let no_focused_note = |action: Action| !action.needs_focused_note();
+
+        let mut input = key_press(Key::Tab, Modifiers::NONE);
+        assert!(take_actions(&mut input, false, &keymap, no_focused_note).is_empty());
+        assert_eq!(input.events.len(), 1);
+
+        let mut input = key_press(Key::Tab, Modifiers::NONE);
+        assert_eq!(take_actions(&mut input, false, &keymap, |_| true), vec![Action::IndentNote]);
+        assert!(input.events.is_empty());
+
+        let mut input = key_press(Key::N, Modifiers::COMMAND);
+        assert_eq!(take_actions(&mut input, false, &keymap, no_focused_note), vec![Action::NewNote]);
+    }
+
+    #[test]
+    fn typing_keeps_plain_keys() {
+        let keymap = Keymap::default();
+
+        let mut input = key_press(Key::Space, Modifiers::NONE);
+        assert!(take_actions(&mut input, true, &keymap, |_| true).is_empty());
+        assert_eq!(input.events.len(), 1);
+
+        let mut input = key_press(Key::F, Modifiers::COMMAND);
+        assert_eq!(take_actions(&mut input, true, &keymap, |_| true), vec![Action::Search]);
+    }
+}
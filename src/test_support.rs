@@ -0,0 +1,16 @@
+// Builders and views over pages that the tests of several modules share.
+use crate::todo_func::{AppState, Content};
+
+pub fn texts(state: &AppState) -> Vec<&str> {
+    state.list.iter().map(|content| content.text.as_str()).collect()
+}
+
+// Notes from `(text, depth)` pairs, in that order.
+pub fn outline(notes: &[(&str, usize)]) -> AppState {
+    let list = notes.iter().map(|&(text, depth)| Content { depth, ..Content::new(String::from(text)) }).collect();
+    AppState { list, ..AppState::default() }
+}
+
+pub fn depths(state: &AppState) -> Vec<(&str, usize)> {
+    state.list.iter().map(|content| (content.text.as_str(), content.depth)).collect()
+}
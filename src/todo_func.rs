@@ -1,26 +1,765 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
-use eframe::{egui::{self, FontFamily, FontId, Id, Key, TextStyle, Ui, Visuals}, App};
+use chrono::{DateTime, Datelike, Local, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use eframe::{egui::{self, Color32, FontFamily, FontId, Id, Key, TextStyle, Ui, Visuals}, App};
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
+use crate::history::{History, DEFAULT_HISTORY_DEPTH};
 use crate::json_parser;
+use crate::recurrence::Recurrence;
+use crate::reminders::{Reminder, ReminderScheduler};
+use crate::shortcuts::{self, Action, Keymap};
+use crate::ui::command_palette::CommandPalette;
+use crate::ui::search::GlobalSearch;
+use crate::ui::due_date::DueEditor;
+use crate::ui::tags::TagEditor;
 
-#[derive(Serialize, Deserialize, Default)]
+#[derive(Serialize, Deserialize, Default, Clone)]
 pub struct AppState {
     pub list: Vec<Content>,
+    #[serde(default)]
+    pub view: NoteView,
+    // A note shown for now even though the view, the archive or a collapsed parent would hide it.
+    // Nothing about the page changes, and it is forgotten once another page is opened or the view changes.
+    #[serde(skip)]
+    pub revealed: Option<Uuid>,
 }
 
-#[derive(Serialize, Deserialize, Default)]
+// Subtasks are kept in the same flat list as an outline: every note is a child of the closest
+// note above it with a smaller depth, and a note's subtree is the run of deeper notes right after it.
+impl AppState {
+    // Indices into `list` in the order they are shown. Notes inside collapsed parents are left out, and
+    // so are the ones hidden by the view unless one of their subtasks is still shown. The revealed note
+    // and its parents are always shown.
+    pub fn visible_indices(&self) -> Vec<usize> {
+        let mut indices = Vec::new();
+        self.push_visible(self.children(None), &mut indices);
+        indices
+    }
+
+    fn push_visible(&self, siblings: Vec<usize>, indices: &mut Vec<usize>) {
+        let now = Local::now().naive_local();
+        let revealed = self.revealed.and_then(|id| self.index_of(id));
+        let leads_to_revealed = |i: usize| revealed.is_some_and(|revealed| self.subtree(i).contains(&revealed));
+        let mut siblings: Vec<usize> = siblings.into_iter()
+            .filter(|&i| leads_to_revealed(i) || (!self.list[i].archived && self.subtree(i).any(|j| self.view.shows(&self.list[j], now))))
+            .collect();
+
+        // Sorting is stable, so notes that compare equal keep their manual order.
+        let list = &self.list;
+        match self.view.sort {
+            NoteSort::Manual => {},
+            NoteSort::Text => siblings.sort_by_cached_key(|&i| list[i].text.to_lowercase()),
+            NoteSort::Newest => siblings.sort_by_key(|&i| std::cmp::Reverse(list[i].created)),
+            NoteSort::DueDate => siblings.sort_by_key(|&i| list[i].due.map_or((1, None), |due| (0, Some(due.deadline())))),
+            NoteSort::Priority => siblings.sort_by_key(|&i| std::cmp::Reverse(list[i].priority)),
+        }
+
+        for index in siblings {
+            indices.push(index);
+            if !self.list[index].collapsed || leads_to_revealed(index) {
+                self.push_visible(self.children(Some(index)), indices);
+            }
+        }
+    }
+
+    // Makes sure the note is shown, without touching the note, its parents or the view.
+    pub fn reveal(&mut self, id: Uuid) {
+        self.revealed = None;
+        let hidden = self.index_of(id).is_some_and(|index| !self.visible_indices().contains(&index));
+        if hidden {
+            self.revealed = Some(id);
+        }
+    }
+
+    pub fn index_of(&self, id: Uuid) -> Option<usize> {
+        self.list.iter().position(|content| content.id == id)
+    }
+
+    // The note itself followed by all of its subtasks.
+    pub fn subtree(&self, index: usize) -> std::ops::Range<usize> {
+        let depth = self.list[index].depth;
+        let end = self.list[index + 1..].iter().position(|content| content.depth <= depth)
+            .map_or(self.list.len(), |offset| index + 1 + offset);
+        index..end
+    }
+
+    // Direct subtasks of a note, or the top level notes for `None`.
+    pub fn children(&self, parent: Option<usize>) -> Vec<usize> {
+        let (range, depth) = parent.map_or((0..self.list.len(), 0), |index| {
+            let subtree = self.subtree(index);
+            (subtree.start + 1..subtree.end, self.list[index].depth + 1)
+        });
+        range.filter(|&i| self.list[i].depth == depth).collect()
+    }
+
+    pub fn parent(&self, index: usize) -> Option<usize> {
+        let depth = self.list[index].depth;
+        self.list[..index].iter().rposition(|content| content.depth < depth)
+    }
+
+    // How many direct subtasks are checked, and how many there are.
+    pub fn progress(&self, index: usize) -> Option<(usize, usize)> {
+        let children = self.children(Some(index));
+        let done = children.iter().filter(|&&i| self.list[i].is_checked).count();
+        (!children.is_empty()).then_some((done, children.len()))
+    }
+
+    // Makes the note (with its own subtasks) a subtask of the sibling above it. Only done while the notes are
+    // shown in their saved order and that sibling is shown too, so the note never lands under an unexpected parent.
+    pub fn indent(&mut self, index: usize) -> bool {
+        let previous_sibling = self.children(self.parent(index)).into_iter().rev().find(|&i| i < index);
+        let can_indent = self.view.sort == NoteSort::Manual
+            && previous_sibling.is_some_and(|sibling| self.visible_indices().contains(&sibling));
+        if can_indent {
+            for i in self.subtree(index) {
+                self.list[i].depth += 1;
+            }
+        }
+        can_indent
+    }
+
+    pub fn outdent(&mut self, index: usize) -> bool {
+        let can_outdent = self.view.sort == NoteSort::Manual && self.list[index].depth > 0;
+        if can_outdent {
+            for i in self.subtree(index) {
+                self.list[i].depth -= 1;
+            }
+        }
+        can_outdent
+    }
+
+    // Returns the history label for the change. Checking a recurring note moves it to its next
+    // occurrence instead, with its subtasks reopened for the next round.
+    pub fn set_checked(&mut self, index: usize, checked: bool, check_parents: bool) -> &'static str {
+        self.list[index].is_checked = checked;
+
+        if checked && self.list[index].reschedule(Local::now().date_naive()) {
+            for i in self.subtree(index).skip(1) {
+                self.list[i].is_checked = false;
+            }
+            return "Complete recurring note";
+        }
+
+        if check_parents {
+            self.update_parents(index);
+        }
+        if checked {"Check note"} else {"Uncheck note"}
+    }
+
+    // Takes the notes with their subtasks out of the page, or copies them under new ids. Each picked note
+    // becomes a top level note with its subtasks kept under it.
+    pub fn take_notes(&mut self, indices: &[usize], copy: bool) -> Vec<Content> {
+        let mut picked: Vec<usize> = indices.iter().flat_map(|&index| self.subtree(index)).collect();
+        picked.sort_unstable();
+        picked.dedup();
+
+        let mut notes = Vec::new();
+        let mut root_depth = 0;
+        for (position, &index) in picked.iter().enumerate() {
+            let mut content = self.list[index].clone();
+            // A note starts a new group unless its parent came along with it.
+            if position == 0 || picked[position - 1] + 1 != index || content.depth <= root_depth {
+                root_depth = content.depth;
+            }
+            content.depth -= root_depth;
+            if copy {
+                content.id = Uuid::new_v4();
+                content.created = Utc::now();
+            }
+            notes.push(content);
+        }
+
+        if !copy {
+            for &index in picked.iter().rev() {
+                self.list.remove(index);
+            }
+            self.normalize_depths();
+        }
+        notes
+    }
+
+    // Moves the note with its subtasks next to `target`, as a sibling of it. Going after a note means
+    // going after its subtasks too. Returns the note's new index, or nothing if it would land inside itself.
+    pub fn move_note(&mut self, from: usize, target: usize, after: bool) -> Option<usize> {
+        let subtree = self.subtree(from);
+        if subtree.contains(&target) {
+            return None;
+        }
+
+        let (from_depth, depth) = (self.list[from].depth, self.list[target].depth);
+        let insert_at = if after { self.subtree(target).end } else { target };
+        let insert_at = if insert_at > subtree.start { insert_at - subtree.len() } else { insert_at };
+
+        let mut moved: Vec<Content> = self.list.drain(subtree).collect();
+        for content in &mut moved {
+            content.depth = content.depth - from_depth + depth;
+        }
+        self.list.splice(insert_at..insert_at, moved);
+        self.normalize_depths();
+        Some(insert_at)
+    }
+
+    // Swaps the note with the sibling above or below it. Returns the note's new index.
+    pub fn move_among_siblings(&mut self, index: usize, up: bool) -> Option<usize> {
+        let siblings: Vec<usize> = self.children(self.parent(index)).into_iter().filter(|&i| !self.list[i].archived).collect();
+        let position = siblings.iter().position(|&i| i == index)?;
+        let target = if up { *siblings.get(position.checked_sub(1)?)? } else { *siblings.get(position + 1)? };
+        self.move_note(index, target, !up)
+    }
+
+    // Checks every parent whose subtasks are now all done, or unchecks the parents of a note that was reopened.
+    pub fn update_parents(&mut self, index: usize) {
+        let mut child = index;
+        while let Some(parent) = self.parent(child) {
+            let all_done = self.children(Some(parent)).iter().all(|&i| self.list[i].is_checked);
+            if self.list[parent].is_checked == all_done {
+                break;
+            }
+            self.list[parent].is_checked = all_done;
+            child = parent;
+        }
+    }
+
+    // Notes that are checked along with all of their subtasks.
+    pub fn completed(&self) -> Vec<usize> {
+        (0..self.list.len()).filter(|&i| self.subtree(i).all(|j| self.list[j].is_checked)).collect()
+    }
+
+    // Top level notes that were put away in the "Completed" section.
+    pub fn archived(&self) -> Vec<usize> {
+        self.children(None).into_iter().filter(|&i| self.list[i].archived).collect()
+    }
+
+    // Only whole top level notes are archived, so the outline of the notes left in the list stays intact.
+    // Returns true if any note was archived.
+    pub fn archive_completed(&mut self) -> bool {
+        let roots: Vec<usize> = self.children(None).into_iter()
+            .filter(|&i| !self.list[i].archived && self.subtree(i).all(|j| self.list[j].is_checked))
+            .collect();
+
+        for &root in &roots {
+            for i in self.subtree(root) {
+                self.list[i].archived = true;
+            }
+        }
+        !roots.is_empty()
+    }
+
+    // Puts the top level note back into the list. Returns false if it wasn't archived.
+    pub fn unarchive(&mut self, root: usize) -> bool {
+        if !self.list[root].archived {
+            return false;
+        }
+        for i in self.subtree(root) {
+            self.list[i].archived = false;
+        }
+        true
+    }
+
+    // Unchecks every note, archived ones included, so the page can be used as a fresh checklist.
+    // Returns true if anything changed.
+    pub fn reset_checks(&mut self) -> bool {
+        let mut changed = false;
+        for content in &mut self.list {
+            changed |= content.is_checked || content.archived;
+            content.is_checked = false;
+            content.archived = false;
+        }
+        changed
+    }
+
+    // Keeps the outline valid after notes were removed or moved, so no note is deeper than one below its parent.
+    pub fn normalize_depths(&mut self) {
+        let mut max_depth = 0;
+        for content in &mut self.list {
+            content.depth = content.depth.min(max_depth);
+            max_depth = content.depth + 1;
+        }
+    }
+}
+
+// How the notes of a page are shown. Saved with the page so each one keeps its own.
+#[derive(Serialize, Deserialize, Default, Clone, PartialEq, Eq)]
+pub struct NoteView {
+    pub sort: NoteSort,
+    pub min_priority: Priority,
+    #[serde(default)]
+    pub hide_completed: bool,
+    #[serde(default)]
+    pub filter: NoteFilter,
+}
+
+impl NoteView {
+    pub fn shows(&self, content: &Content, now: NaiveDateTime) -> bool {
+        let matches_filter = match &self.filter {
+            NoteFilter::All => true,
+            NoteFilter::Overdue => content.due_status(now) == Some(DueStatus::Overdue),
+            NoteFilter::Tagged(tag) => content.has_tag(tag),
+        };
+
+        matches_filter && content.priority >= self.min_priority && !(self.hide_completed && content.is_checked)
+    }
+
+    pub fn is_filtered(&self) -> bool {
+        self.min_priority != Priority::None || self.hide_completed || self.filter != NoteFilter::All
+    }
+
+    pub fn clear_filters(&mut self) {
+        self.min_priority = Priority::None;
+        self.hide_completed = false;
+        self.filter = NoteFilter::All;
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Clone, PartialEq, Eq)]
+pub enum NoteFilter {
+    #[default]
+    All,
+    Overdue,
+    Tagged(String),
+}
+
+impl NoteFilter {
+    pub fn label(&self) -> String {
+        match self {
+            Self::All => String::from("All notes"),
+            Self::Overdue => String::from("Overdue"),
+            Self::Tagged(tag) => format!("#{tag}"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Clone, Copy, PartialEq, Eq)]
+pub enum NoteSort {
+    #[default]
+    Manual,
+    Text,
+    Newest,
+    DueDate,
+    Priority,
+}
+
+impl NoteSort {
+    pub const ALL: [Self; 5] = [Self::Manual, Self::Text, Self::Newest, Self::DueDate, Self::Priority];
+
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Manual => "Manual",
+            Self::Text => "Text",
+            Self::Newest => "Newest first",
+            Self::DueDate => "Due date",
+            Self::Priority => "Priority",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Clone)]
 pub struct Content{
+    // Notes saved before ids existed get a fresh one when they are first read.
+    #[serde(default = "Uuid::new_v4")]
+    pub id: Uuid,
     pub text: String,
-    pub is_checked: bool
+    pub is_checked: bool,
+    #[serde(default)]
+    pub due: Option<Due>,
+    #[serde(default)]
+    pub priority: Priority,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub depth: usize,
+    #[serde(default)]
+    pub collapsed: bool,
+    // Set on a completed top level note and its subtasks, which are then only listed under "Completed".
+    #[serde(default)]
+    pub archived: bool,
+    #[serde(default)]
+    pub recurrence: Option<Recurrence>,
+    #[serde(default)]
+    pub reminder: Option<Reminder>,
+    // Notes from before this was recorded count as the oldest.
+    #[serde(default)]
+    pub created: DateTime<Utc>,
 }
 
+impl Content {
+    pub fn new(text: String) -> Self {
+        Self { id: Uuid::new_v4(), text, created: Utc::now(), ..Default::default() }
+    }
+
+    // Moves a recurring note to its next occurrence that isn't in the past and reopens it.
+    // Returns false for notes that don't repeat.
+    pub fn reschedule(&mut self, today: NaiveDate) -> bool {
+        let Some(recurrence) = self.recurrence else {
+            return false;
+        };
+
+        let mut due = self.due.unwrap_or_else(Due::today);
+        due.date = recurrence.next(due.date);
+        while due.date < today {
+            due.date = recurrence.next(due.date);
+        }
+
+        self.due = Some(due);
+        self.is_checked = false;
+        if let Some(reminder) = &mut self.reminder {
+            reminder.snoozed_until = None;
+        }
+        true
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|own| own == tag)
+    }
+
+    // Checked notes are done, so they are never shown as overdue or due today.
+    pub fn due_status(&self, now: NaiveDateTime) -> Option<DueStatus> {
+        self.due.filter(|_| !self.is_checked).map(|due| due.status(now))
+    }
+}
+
+// Tags are compared case-insensitively, so they are stored lowercase and without the leading '#'.
+pub fn normalize_tag(word: &str) -> Option<String> {
+    let tag = word.trim_start_matches('#').to_lowercase();
+    (!tag.is_empty()).then_some(tag)
+}
+
+// Splits the `#tag` words out of what was typed in the add panel, returning the remaining text and the tags.
+pub fn parse_tags(input: &str) -> (String, Vec<String>) {
+    let mut words = Vec::new();
+    let mut tags: Vec<String> = Vec::new();
+
+    for word in input.split_whitespace() {
+        match word.strip_prefix('#').and_then(normalize_tag) {
+            Some(tag) => if !tags.contains(&tag) { tags.push(tag) },
+            None => words.push(word),
+        }
+    }
+
+    (words.join(" "), tags)
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct Due {
+    pub date: NaiveDate,
+    pub time: Option<NaiveTime>
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum DueStatus {
+    Overdue,
+    Today,
+    Upcoming,
+}
+
+impl Due {
+    pub fn today() -> Self {
+        Self { date: Local::now().date_naive(), time: None }
+    }
+
+    // Notes without a time are due at the end of their day.
+    pub fn deadline(self) -> NaiveDateTime {
+        self.time.map_or_else(
+            || self.date.and_time(NaiveTime::MIN) + chrono::Duration::days(1),
+            |time| self.date.and_time(time))
+    }
+
+    pub fn status(self, now: NaiveDateTime) -> DueStatus {
+        if self.deadline() <= now {
+            DueStatus::Overdue
+        } else if self.date == now.date() {
+            DueStatus::Today
+        } else {
+            DueStatus::Upcoming
+        }
+    }
+
+    pub fn label(self) -> String {
+        let date = if self.date.year() == Local::now().year() {
+            self.date.format("%b %-d").to_string()
+        } else {
+            self.date.format("%b %-d, %Y").to_string()
+        };
+
+        match self.time {
+            Some(time) => format!("{date} {}", time.format("%H:%M")),
+            None => date,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Priority {
+    #[default]
+    None,
+    Low,
+    Medium,
+    High,
+    Urgent,
+}
+
+impl Priority {
+    pub const ALL: [Self; 5] = [Self::None, Self::Low, Self::Medium, Self::High, Self::Urgent];
+
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::None => "None",
+            Self::Low => "Low",
+            Self::Medium => "Medium",
+            Self::High => "High",
+            Self::Urgent => "Urgent",
+        }
+    }
+
+    // Notes without a priority get no marker at all.
+    pub const fn color(self) -> Option<Color32> {
+        match self {
+            Self::None => None,
+            Self::Low => Some(Color32::from_rgb(90, 150, 220)),
+            Self::Medium => Some(Color32::from_rgb(220, 190, 60)),
+            Self::High => Some(Color32::from_rgb(235, 130, 40)),
+            Self::Urgent => Some(Color32::from_rgb(220, 50, 50)),
+        }
+    }
+
+    // Stops at the ends instead of wrapping around, so holding the key can't skip from urgent to none.
+    pub fn step(self, step: isize) -> Self {
+        let index = Self::ALL.iter().position(|priority| *priority == self).unwrap_or_default();
+        Self::ALL[index.saturating_add_signed(step).min(Self::ALL.len() - 1)]
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct Page {
+    pub id: Uuid,
+    pub title: String,
+    pub state: AppState,
+    #[serde(default)]
+    pub modified: DateTime<Utc>
+}
+
+impl Page {
+    pub fn new(title: String) -> Self {
+        Self { id: Uuid::new_v4(), title, state: AppState::default(), modified: Utc::now() }
+    }
+
+    pub fn open_items(&self) -> usize {
+        self.state.list.iter().filter(|content| !content.is_checked).count()
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Clone, Copy, PartialEq, Eq)]
+pub enum PageSort {
+    #[default]
+    Manual,
+    Alphabetical,
+    RecentlyModified,
+    MostOpenItems,
+}
+
+impl PageSort {
+    pub const ALL: [Self; 4] = [Self::Manual, Self::Alphabetical, Self::RecentlyModified, Self::MostOpenItems];
+
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Manual => "Manual",
+            Self::Alphabetical => "Alphabetical",
+            Self::RecentlyModified => "Recently Modified",
+            Self::MostOpenItems => "Most Open Items",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub enum TrashedItem {
+    // The note first, followed by its subtasks with depths counted from it.
+    Note(Vec<Content>),
+    Page(Page),
+}
 
-#[derive(Serialize, Deserialize, Default)]
+#[derive(Serialize, Deserialize, Clone)]
+pub struct TrashItem {
+    pub item: TrashedItem,
+    pub origin_page: Uuid,
+    pub origin_title: String,
+    pub deleted: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize)]
 pub struct StateList {
-    pub list: HashMap<String, String>,
-    pub current_app_state: String
+    pub schema_version: u32,
+    pub list: Vec<Page>,
+    pub current_page: Option<Uuid>,
+    #[serde(default)]
+    pub page_sort: PageSort,
+    #[serde(default)]
+    pub trash: Vec<TrashItem>
+}
+
+impl Default for StateList {
+    fn default() -> Self {
+        Self {
+            schema_version: json_parser::STATE_LIST_VERSION,
+            list: Vec::new(),
+            current_page: None,
+            page_sort: PageSort::default(),
+            trash: Vec::new(),
+        }
+    }
+}
+
+impl StateList {
+    pub fn contains_title(&self, title: &str) -> bool {
+        self.list.iter().any(|page| page.title == title)
+    }
+
+    pub fn can_rename_page(&self, id: Uuid, title: &str) -> bool {
+        !title.is_empty() && !self.list.iter().any(|page| page.id != id && page.title == title)
+    }
+
+    pub fn rename_page(&mut self, id: Uuid, title: String) {
+        if let Some(page) = self.page_mut(id) {
+            page.title = title;
+            page.modified = Utc::now();
+        }
+    }
+
+    pub fn page(&self, id: Uuid) -> Option<&Page> {
+        self.list.iter().find(|page| page.id == id)
+    }
+
+    pub fn page_mut(&mut self, id: Uuid) -> Option<&mut Page> {
+        self.list.iter_mut().find(|page| page.id == id)
+    }
+
+    pub fn current(&self) -> Option<&Page> {
+        self.current_page.and_then(|id| self.page(id))
+    }
+
+    pub fn current_mut(&mut self) -> Option<&mut Page> {
+        self.current_page.and_then(|id| self.page_mut(id))
+    }
+
+    pub fn current_title(&self) -> Option<&str> {
+        self.current().map(|page| page.title.as_str())
+    }
+
+    // Every tag used on any page, with how many notes carry it.
+    pub fn tag_counts(&self) -> BTreeMap<&str, usize> {
+        let mut counts = BTreeMap::new();
+        for content in self.list.iter().flat_map(|page| &page.state.list) {
+            for tag in &content.tags {
+                *counts.entry(tag.as_str()).or_default() += 1;
+            }
+        }
+        counts
+    }
+
+    pub fn trash_page(&mut self, id: Uuid) {
+        let Some(index) = self.list.iter().position(|page| page.id == id) else {
+            return;
+        };
+
+        let page = self.list.remove(index);
+        self.trash.push(TrashItem {
+            origin_page: page.id,
+            origin_title: page.title.clone(),
+            item: TrashedItem::Page(page),
+            deleted: Utc::now(),
+        });
+    }
+
+    pub fn trash_note(&mut self, notes: Vec<Content>) {
+        let (origin_page, origin_title) = self.current()
+            .map(|page| (page.id, page.title.clone()))
+            .unwrap_or_default();
+
+        self.trash.push(TrashItem {
+            item: TrashedItem::Note(notes),
+            origin_page,
+            origin_title,
+            deleted: Utc::now(),
+        });
+    }
+
+    // Notes go back to the page they came from, which is recreated if it has been deleted since.
+    pub fn restore_from_trash(&mut self, index: usize) {
+        if index >= self.trash.len() {
+            return;
+        }
+
+        let trashed = self.trash.remove(index);
+        match trashed.item {
+            TrashedItem::Note(notes) => {
+                let origin = self.list.iter().position(|page| page.id == trashed.origin_page)
+                    .or_else(|| self.list.iter().position(|page| page.title == trashed.origin_title));
+
+                let page = if let Some(origin) = origin {
+                    &mut self.list[origin]
+                } else {
+                    self.list.push(Page { id: trashed.origin_page, ..Page::new(trashed.origin_title) });
+                    let last = self.list.len() - 1;
+                    &mut self.list[last]
+                };
+                // The parent it was nested under may be gone, so it comes back as a top level note in the list.
+                page.state.list.extend(notes.into_iter().map(|content| Content { archived: false, ..content }));
+                page.state.normalize_depths();
+                page.modified = Utc::now();
+            },
+            // Restoring one of its notes may have recreated the page already, in which case the two are merged
+            // back into one so no two pages ever share an id.
+            TrashedItem::Page(mut page) => {
+                let recreated = self.list.iter().position(|existing| existing.id == page.id);
+                if let Some(position) = recreated {
+                    page.state.list.extend(self.list.remove(position).state.list);
+                    page.state.normalize_depths();
+                    page.modified = Utc::now();
+                }
+
+                while self.contains_title(&page.title) {
+                    page.title = format!("{} (restored)", page.title);
+                }
+
+                match recreated {
+                    Some(position) => self.list.insert(position, page),
+                    None => self.list.push(page),
+                }
+            },
+        }
+    }
+
+    pub fn delete_from_trash(&mut self, index: usize) {
+        if index < self.trash.len() {
+            self.trash.remove(index);
+        }
+    }
+
+    pub fn purge_trash(&mut self, retention_days: u32) {
+        let cutoff = Utc::now() - chrono::Duration::days(i64::from(retention_days));
+        self.trash.retain(|item| item.deleted > cutoff);
+    }
+
+    // Moves the page at `from` so that it ends up at index `to`.
+    pub fn move_page(&mut self, from: usize, to: usize) {
+        if from < self.list.len() && to < self.list.len() {
+            let page = self.list.remove(from);
+            self.list.insert(to, page);
+        }
+    }
+
+    // Indices into `list` in the order the side panel should show them.
+    pub fn sorted_indices(&self) -> Vec<usize> {
+        let mut indices: Vec<usize> = (0..self.list.len()).collect();
+        match self.page_sort {
+            PageSort::Manual => {},
+            PageSort::Alphabetical => indices.sort_by_cached_key(|&i| self.list[i].title.to_lowercase()),
+            PageSort::RecentlyModified => indices.sort_by(|&a, &b| self.list[b].modified.cmp(&self.list[a].modified)),
+            PageSort::MostOpenItems => indices.sort_by_key(|&i| std::cmp::Reverse(self.list[i].open_items())),
+        }
+        indices
+    }
 }
 
 #[derive(Default)]
@@ -28,7 +767,27 @@ pub struct TodoApp{
     pub state: AppState,
     pub state_list: StateList,
     pub panel_manager: PanelManager,
-    pub dark_mode: Theme
+    pub dark_mode: Theme,
+    pub editing_note: Option<Uuid>,
+    pub renaming_page: Option<Uuid>,
+    pub focused_note: Option<Uuid>,
+    pub pending_actions: Vec<Action>,
+    pub rebinding_action: Option<Action>,
+    pub command_palette: CommandPalette,
+    pub search: GlobalSearch,
+    pub flash_note: Option<Uuid>,
+    pub selected_notes: Vec<Uuid>,
+    pub selection_anchor: Option<Uuid>,
+    pub bulk_due: Option<Due>,
+    pub pending_due: Option<Due>,
+    // An existing note that matches what was typed in the add panel, while asking whether to add it anyway.
+    pub pending_duplicate: Option<Uuid>,
+    pub due_editor: Option<DueEditor>,
+    pub tag_editor: Option<TagEditor>,
+    pub tag_view: Option<String>,
+    pub history: History,
+    pub reminders: ReminderScheduler,
+    pub recovery: Option<json_parser::Recovery>
 }
 
 #[allow(clippy::struct_excessive_bools)]
@@ -40,6 +799,11 @@ pub struct PanelManager {
     pub settings_visible: bool,
     pub reset_popup_visible: bool,
     pub delete_page_popup_visible: bool,
+    pub trash_visible: bool,
+    pub shortcuts_visible: bool,
+    pub command_palette_visible: bool,
+    pub search_visible: bool,
+    pub duplicates_visible: bool,
 }
 
 impl PanelManager {
@@ -66,28 +830,125 @@ impl PanelManager {
     pub fn show_add_page_panel(&mut self, visible: bool) {
         self.add_page_panel_visible = visible;
     }
+
+    pub fn show_trash(&mut self, visible: bool) {
+        self.trash_visible = visible;
+    }
+
+    pub fn show_shortcuts(&mut self, visible: bool) {
+        self.shortcuts_visible = visible;
+    }
+
+    pub fn show_command_palette(&mut self, visible: bool) {
+        self.command_palette_visible = visible;
+    }
+
+    pub fn show_search(&mut self, visible: bool) {
+        self.search_visible = visible;
+    }
+
+    pub fn show_duplicates(&mut self, visible: bool) {
+        self.duplicates_visible = visible;
+    }
 }
 
-#[derive(Serialize, Deserialize, Default)]
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Serialize, Deserialize)]
 pub struct Theme {
-    pub is_dark_mode: bool
+    pub schema_version: u32,
+    pub is_dark_mode: bool,
+    #[serde(default = "default_history_depth")]
+    pub history_depth: usize,
+    #[serde(default = "default_trash_retention_days")]
+    pub trash_retention_days: u32,
+    #[serde(default)]
+    pub keymap: Keymap,
+    #[serde(default)]
+    pub auto_check_parents: bool,
+    #[serde(default)]
+    pub desktop_notifications: bool,
+    #[serde(default)]
+    pub fuzzy_duplicates: bool,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            schema_version: json_parser::THEME_VERSION,
+            is_dark_mode: false,
+            history_depth: DEFAULT_HISTORY_DEPTH,
+            trash_retention_days: DEFAULT_TRASH_RETENTION_DAYS,
+            keymap: Keymap::default(),
+            auto_check_parents: false,
+            desktop_notifications: false,
+            fuzzy_duplicates: false,
+        }
+    }
+}
+
+const DEFAULT_TRASH_RETENTION_DAYS: u32 = 30;
+
+const fn default_history_depth() -> usize {
+    DEFAULT_HISTORY_DEPTH
+}
+
+const fn default_trash_retention_days() -> u32 {
+    DEFAULT_TRASH_RETENTION_DAYS
 }
 
 impl App for TodoApp {
 
-    fn raw_input_hook(&mut self, _ctx: &eframe::egui::Context, _raw_input: &mut eframe::egui::RawInput) {} // TODO
+    fn raw_input_hook(&mut self, ctx: &eframe::egui::Context, raw_input: &mut eframe::egui::RawInput) {
+        if self.rebinding_action.is_some() {
+            if let Some(shortcut) = shortcuts::take_chord(raw_input) {
+                self.finish_rebinding(ctx, shortcut);
+            }
+            return;
+        }
+
+        let typing = ctx.wants_keyboard_input();
+        let note_focused = self.focused_note.and_then(|id| self.state.index_of(id)).is_some();
+        let widget_focused = ctx.memory(|mem| mem.focused().is_some());
+        // Without a focused note, Tab, Space and the arrow keys are left to egui's own focus handling.
+        // Moving between notes can still start while no widget has the focus.
+        let can_run = |action: Action| match action {
+            Action::NextNote | Action::PreviousNote => note_focused || !widget_focused,
+            action => note_focused || !action.needs_focused_note(),
+        };
+        self.pending_actions.extend(shortcuts::take_actions(raw_input, typing, &self.dark_mode.keymap, can_run));
+    }
     
     fn update(&mut self, ctx: &eframe::egui::Context, frame: &mut eframe::Frame) {
+        for action in std::mem::take(&mut self.pending_actions) {
+            self.run_action(ctx, action);
+        }
+        self.check_reminders(ctx);
+
         self.render(ctx, frame);
         self.render_popups(ctx);
+        self.render_trash(ctx);
+        self.render_command_palette(ctx);
+        self.render_search(ctx);
+        self.render_due_editor(ctx);
+        self.render_tag_editor(ctx);
+        self.render_tag_view(ctx);
+        self.render_duplicates(ctx);
     }
     fn persist_egui_memory(&self) -> bool {
         true
     }
 
     fn save(&mut self, storage: &mut dyn eframe::Storage) {
-        if let Err(e) = json_parser::save_state_list(&self.state_list, storage){
-            eprintln!("Error while saving state_listL {e}");
+        // Without a backup, the unreadable data is only kept around until the user has been told about it.
+        let keep_unreadable_data = self.recovery.as_ref().is_some_and(|recovery| recovery.backup_path.is_none());
+
+        // eframe saves every so often while the app is open, which is also when old trash is let go.
+        self.state_list.purge_trash(self.dark_mode.trash_retention_days);
+
+        if !keep_unreadable_data {
+            if let Err(e) = json_parser::save_state_list(&self.state_list, storage){
+                eprintln!("Error while saving state_listL {e}");
+            }
         }
 
         if let Err(e) = json_parser::save_theme(&self.dark_mode) {
@@ -102,13 +963,28 @@ impl TodoApp {
     pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
         configure_fonts(&cc.egui_ctx);
 
-        let mut state_list = json_parser::read_state_list(cc).unwrap_or_default();
+        let (mut state_list, recovery) = match json_parser::read_state_list(cc) {
+            Ok(state_list) => (state_list, None),
+            Err(e) => {
+                eprintln!("Failed to read state list: {e}");
+                let (state_list, recovery) = json_parser::recover_state_list(cc, &e);
+                (state_list, Some(recovery))
+            }
+        };
         let state = AppState::default();
-        state_list.current_app_state = String::new();
+        state_list.current_page = None;
 
-        let dark_mode = json_parser::read_theme().unwrap_or(Theme {is_dark_mode: true});
+        let dark_mode = json_parser::read_theme().unwrap_or_else(|e| {
+            eprintln!("Failed to read settings: {e}");
+            match json_parser::back_up_theme() {
+                Ok(path) => eprintln!("The unreadable settings were moved to {}", path.display()),
+                Err(e) => eprintln!("Failed to back up unreadable settings: {e}"),
+            }
+            Theme {is_dark_mode: true, ..Default::default()}
+        });
+        state_list.purge_trash(dark_mode.trash_retention_days);
 
-        Self { state, state_list, dark_mode, ..Default::default() }
+        Self { state, state_list, dark_mode, recovery, ..Default::default() }
     }
 }
 
@@ -155,38 +1031,106 @@ impl TodoApp {
         });
     }
 
-    pub fn delete_content(&mut self, arr: &mut Vec<usize>){
-        if arr.len() > 1 {
-            arr.sort_unstable();
-            arr.reverse();
+    // Subtasks go along with their parent, and each note goes to the trash together with them.
+    pub fn delete_content(&mut self, indices: &[usize]){
+        let mut groups: Vec<Vec<Content>> = Vec::new();
+        for content in self.state.take_notes(indices, false) {
+            match groups.last_mut() {
+                Some(group) if content.depth > 0 => group.push(content),
+                _ => groups.push(vec![content]),
+            }
         }
 
-        for index in arr {
-            self.state.list.remove(*index);
+        for notes in groups {
+            self.state_list.trash_note(notes);
         }
     }
 
     pub fn update_state(&mut self) {
-        let state_as_json = json_parser::state_to_json_string(&self.state);
-        self.state_list.list.insert(self.state_list.current_app_state.clone(), state_as_json);
+        if let Some(page) = self.state_list.current_mut() {
+            page.state.clone_from(&self.state);
+            page.modified = Utc::now();
+        }
+    }
+
+    // Changes a note on any page. `self.state` is the working copy of the current page, so notes on it are
+    // changed there and synced back, while the other pages are changed in place.
+    pub fn edit_note(&mut self, page: Uuid, note: Uuid, history_label: Option<&'static str>, edit: impl FnOnce(&mut AppState, usize)) {
+        if self.is_current_page(page) {
+            let Some(index) = self.state.index_of(note) else {
+                return;
+            };
+            edit(&mut self.state, index);
+            if let Some(label) = history_label {
+                self.record_history(label);
+            }
+            self.update_state();
+            return;
+        }
+
+        if self.state_list.page(page).and_then(|page| page.state.index_of(note)).is_none() {
+            return;
+        }
+        if let Some(label) = history_label {
+            self.record_history(label);
+        }
+        if let Some(page) = self.state_list.page_mut(page) {
+            if let Some(index) = page.state.index_of(note) {
+                edit(&mut page.state, index);
+                page.modified = Utc::now();
+            }
+        }
     }
 
-    pub fn no_page_selected(&self) -> bool {
-        self.state_list.current_app_state.is_empty()
+    // Moves or copies notes of the current page to the end of another page, changing both in one step.
+    pub fn send_notes(&mut self, indices: &[usize], target: Uuid, copy: bool) {
+        if self.is_current_page(target) || self.state_list.page(target).is_none() || indices.is_empty() {
+            return;
+        }
+
+        let notes = self.state.take_notes(indices, copy);
+        let count = notes.len();
+        self.record_history(match (copy, count) {
+            (true, 1) => "Copy note",
+            (true, _) => "Copy notes",
+            (false, 1) => "Move note",
+            (false, _) => "Move notes",
+        });
+
+        if let Some(page) = self.state_list.page_mut(target) {
+            page.state.list.extend(notes);
+            page.modified = Utc::now();
+        }
+        if !copy {
+            self.focused_note = None;
+            self.update_state();
+        }
+    }
+
+    pub const fn no_page_selected(&self) -> bool {
+        self.state_list.current_page.is_none()
     }
 
     pub fn enter_key_pressed(ui: &Ui) -> bool {
         ui.input(|i| i.key_pressed(Key::Enter))
     }
 
-    pub fn is_current_page(&self, title: &str) -> bool {
-        title == self.state_list.current_app_state.as_str()
+    pub fn is_current_page(&self, id: Uuid) -> bool {
+        self.state_list.current_page == Some(id)
     }
 
     pub fn show_updated_state(&mut self) {
-        self.state = json_parser::json_string_to_state(
-            self.state_list.list.get(&self.state_list.current_app_state))
+        self.editing_note = None;
+        self.due_editor = None;
+        self.tag_editor = None;
+        self.focused_note = None;
+        self.selected_notes.clear();
+        self.selection_anchor = None;
+        self.pending_duplicate = None;
+        self.state = self.state_list.current()
+            .map(|page| page.state.clone())
             .unwrap_or_default();
+        self.state.revealed = None;
     }
 
     pub fn update_theme(&self, ctx: &eframe::egui::Context){
@@ -198,15 +1142,254 @@ impl TodoApp {
     }
 
     pub fn delete_data(&mut self){
+        self.record_history("Clear data");
+        self.editing_note = None;
+        self.renaming_page = None;
         self.state = AppState::default();
         self.state_list = StateList::default();
-        self.state_list.current_app_state = String::new();
     }
 
     pub fn delete_page(&mut self){
+        self.record_history("Delete page");
+        self.editing_note = None;
+        self.renaming_page = None;
         self.state = AppState::default();
-        self.state_list.list.remove(&self.state_list.current_app_state);
-        self.state_list.current_app_state = String::new();
+        if let Some(id) = self.state_list.current_page.take() {
+            self.state_list.trash_page(id);
+        }
+    }
+
+    // Must be called before the change lands in `state_list`, since that is what gets saved for undoing. The trash
+    // lives there too, so anything that deletes notes records the history first.
+    pub fn record_history(&mut self, label: &str) {
+        self.history.record(label, &self.state_list, self.dark_mode.history_depth);
+    }
+
+    pub fn undo(&mut self) {
+        if self.history.undo(&mut self.state_list) {
+            self.renaming_page = None;
+            self.show_updated_state();
+        }
+    }
+
+    pub fn redo(&mut self) {
+        if self.history.redo(&mut self.state_list) {
+            self.renaming_page = None;
+            self.show_updated_state();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{depths, outline, texts};
+
+    #[test]
+    fn indent_nests_under_the_sibling_above() {
+        let mut state = outline(&[("a", 0), ("a1", 1), ("b", 0), ("b1", 1)]);
+        assert!(!state.indent(0));
+        assert!(state.indent(2));
+        assert_eq!(depths(&state), vec![("a", 0), ("a1", 1), ("b", 1), ("b1", 2)]);
+
+        assert!(state.outdent(2));
+        assert_eq!(depths(&state), vec![("a", 0), ("a1", 1), ("b", 0), ("b1", 1)]);
+    }
+
+    #[test]
+    fn outline_structure() {
+        let state = outline(&[("a", 0), ("a1", 1), ("a11", 2), ("a2", 1), ("b", 0)]);
+        assert_eq!(state.subtree(0), 0..4);
+        assert_eq!(state.subtree(1), 1..3);
+        assert_eq!(state.children(None), vec![0, 4]);
+        assert_eq!(state.children(Some(0)), vec![1, 3]);
+        assert_eq!(state.parent(2), Some(1));
+        assert_eq!(state.parent(4), None);
+        assert_eq!(state.progress(0), Some((0, 2)));
+        assert_eq!(state.progress(4), None);
+    }
+
+    #[test]
+    fn checking_all_subtasks_checks_the_parent() {
+        let mut state = outline(&[("a", 0), ("a1", 1), ("a2", 1)]);
+        state.set_checked(1, true, true);
+        assert!(!state.list[0].is_checked);
+        state.set_checked(2, true, true);
+        assert!(state.list[0].is_checked);
+        state.set_checked(1, false, true);
+        assert!(!state.list[0].is_checked);
+
+        state.set_checked(1, true, false);
+        assert!(!state.list[0].is_checked);
+    }
+
+    #[test]
+    fn depths_stay_valid_after_removals() {
+        let mut state = outline(&[("a", 0), ("a1", 1), ("a11", 2), ("b", 0)]);
+        state.list.remove(0);
+        state.normalize_depths();
+        assert_eq!(depths(&state), vec![("a1", 0), ("a11", 1), ("b", 0)]);
+    }
+
+    #[test]
+    fn moving_notes_takes_their_subtasks_along() {
+        let mut state = outline(&[("a", 0), ("a1", 1), ("b", 0), ("c", 0)]);
+        assert_eq!(state.move_note(0, 3, true), Some(2));
+        assert_eq!(depths(&state), vec![("b", 0), ("c", 0), ("a", 0), ("a1", 1)]);
+
+        assert_eq!(state.move_note(1, 0, false), Some(0));
+        assert_eq!(depths(&state), vec![("c", 0), ("b", 0), ("a", 0), ("a1", 1)]);
+
+        // Dropped next to a subtask, the note becomes a subtask too.
+        assert_eq!(state.move_note(1, 3, false), Some(2));
+        assert_eq!(depths(&state), vec![("c", 0), ("a", 0), ("b", 1), ("a1", 1)]);
+
+        assert_eq!(state.move_note(1, 3, true), None);
+    }
+
+    #[test]
+    fn moving_among_siblings() {
+        let mut state = outline(&[("a", 0), ("a1", 1), ("a2", 1), ("b", 0)]);
+        assert_eq!(state.move_among_siblings(3, true), Some(0));
+        assert_eq!(depths(&state), vec![("b", 0), ("a", 0), ("a1", 1), ("a2", 1)]);
+
+        assert_eq!(state.move_among_siblings(2, false), Some(3));
+        assert_eq!(depths(&state), vec![("b", 0), ("a", 0), ("a2", 1), ("a1", 1)]);
+
+        assert_eq!(state.move_among_siblings(0, true), None);
+        assert_eq!(state.move_among_siblings(3, false), None);
+    }
+
+    #[test]
+    fn taken_notes_keep_their_subtasks() {
+        let mut state = outline(&[("a", 0), ("a1", 1), ("a11", 2), ("b", 0), ("b1", 1)]);
+        let ids: Vec<Uuid> = state.list.iter().map(|content| content.id).collect();
+
+        let copies = state.take_notes(&[0, 2], true);
+        assert_eq!(copies.iter().map(|content| (content.text.as_str(), content.depth)).collect::<Vec<_>>(),
+            vec![("a", 0), ("a1", 1), ("a11", 2)]);
+        assert!(copies.iter().all(|content| !ids.contains(&content.id)));
+        assert_eq!(state.list.len(), 5);
+
+        let taken = state.take_notes(&[1, 3], false);
+        assert_eq!(taken.iter().map(|content| (content.text.as_str(), content.depth)).collect::<Vec<_>>(),
+            vec![("a1", 0), ("a11", 1), ("b", 0), ("b1", 1)]);
+        assert_eq!(taken[0].id, ids[1]);
+        assert_eq!(depths(&state), vec![("a", 0)]);
+    }
+
+    #[test]
+    fn indent_needs_the_saved_order_and_a_shown_sibling() {
+        let mut state = outline(&[("a", 0), ("b", 0)]);
+        state.view.sort = NoteSort::Text;
+        assert!(!state.indent(1));
+
+        state.view.sort = NoteSort::Manual;
+        state.list[0].is_checked = true;
+        state.view.hide_completed = true;
+        assert!(!state.indent(1));
+
+        state.view.hide_completed = false;
+        assert!(state.indent(1));
+    }
+
+    #[test]
+    fn deleted_notes_are_trashed_and_restored_with_their_subtasks() {
+        let mut app = TodoApp::default();
+        let page = Page::new(String::from("Work"));
+        app.state_list.current_page = Some(page.id);
+        app.state_list.list.push(page);
+        app.state = outline(&[("a", 0), ("a1", 1), ("a2", 1), ("a21", 2), ("b", 0)]);
+
+        app.delete_content(&[2, 0]);
+        assert_eq!(texts(&app.state), vec!["b"]);
+        assert_eq!(app.state_list.trash.len(), 1);
+
+        app.state_list.restore_from_trash(0);
+        assert_eq!(depths(&app.state_list.list[0].state), vec![("a", 0), ("a1", 1), ("a2", 1), ("a21", 2)]);
+
+        // A nested note comes back as a top level note, still with its own subtasks.
+        app.state = outline(&[("a", 0), ("a1", 1), ("a11", 2)]);
+        app.delete_content(&[1]);
+        assert_eq!(texts(&app.state), vec!["a"]);
+        app.state_list.restore_from_trash(0);
+        assert_eq!(depths(&app.state_list.list[0].state)[4..], [("a1", 0), ("a11", 1)]);
+    }
+
+    #[test]
+    fn restoring_a_page_merges_it_with_its_recreated_copy() {
+        let mut state_list = StateList::default();
+        let mut page = Page::new(String::from("Work"));
+        page.state.list.push(Content::new(String::from("Kept")));
+        let id = page.id;
+        state_list.list.push(page);
+        state_list.current_page = Some(id);
+
+        state_list.trash_note(vec![Content::new(String::from("Deleted"))]);
+        state_list.trash_page(id);
+        // The note comes back first, which recreates its page.
+        state_list.restore_from_trash(0);
+        state_list.restore_from_trash(0);
+
+        assert_eq!(state_list.list.len(), 1);
+        assert_eq!(state_list.list[0].id, id);
+        assert_eq!(state_list.list[0].title, "Work");
+        assert_eq!(texts(&state_list.list[0].state), vec!["Kept", "Deleted"]);
+    }
+
+    fn checked(state: &mut AppState, indices: &[usize]) {
+        for &index in indices {
+            state.list[index].is_checked = true;
+        }
+    }
+
+    #[test]
+    fn archive_completed_puts_away_whole_finished_notes() {
+        let mut state = outline(&[("a", 0), ("a1", 1), ("b", 0), ("b1", 1), ("c", 0)]);
+        checked(&mut state, &[0, 1, 2]);
+
+        assert!(state.archive_completed());
+        assert_eq!(state.archived(), vec![0]);
+        assert!(state.list[1].archived);
+        assert!(!state.list[2].archived);
+        assert_eq!(state.visible_indices(), vec![2, 3, 4]);
+        assert!(!state.archive_completed());
+
+        assert!(state.unarchive(0));
+        assert!(!state.unarchive(0));
+        assert!(state.archived().is_empty());
+        assert_eq!(state.visible_indices(), vec![0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn reset_checks_unchecks_archived_notes_too() {
+        let mut state = outline(&[("a", 0), ("a1", 1), ("b", 0)]);
+        checked(&mut state, &[0, 1]);
+        state.archive_completed();
+
+        assert!(state.reset_checks());
+        assert!(state.list.iter().all(|content| !content.is_checked && !content.archived));
+        assert!(!state.reset_checks());
+    }
+
+    #[test]
+    fn reveal_only_changes_what_is_shown() {
+        let mut state = outline(&[("a", 0), ("a1", 1), ("a11", 2), ("b", 0)]);
+        checked(&mut state, &[0, 1, 2]);
+        state.archive_completed();
+        state.list[1].collapsed = true;
+        state.view.hide_completed = true;
+        let hidden = state.list[2].id;
+        let before = state.clone();
+
+        state.reveal(hidden);
+        assert_eq!(state.visible_indices(), vec![0, 1, 2, 3]);
+        assert!(state.list.iter().zip(&before.list).all(|(after, before)| after.archived == before.archived && after.collapsed == before.collapsed));
+        assert!(state.view == before.view);
+
+        // A note that is already shown does not need revealing.
+        state.reveal(state.list[3].id);
+        assert_eq!(state.revealed, None);
+        assert_eq!(state.visible_indices(), vec![3]);
     }
-    
 }
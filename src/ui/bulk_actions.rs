@@ -0,0 +1,173 @@
+use eframe::egui::{Id, Modifiers, RichText, TextEdit, Ui};
+use uuid::Uuid;
+
+use crate::todo_func::{normalize_tag, Priority, TodoApp};
+use crate::ui::due_date::due_date_editor;
+
+const PADDING: f32 = 5.0;
+const TEMP_BULK_TAG_ID_NAME: &str = "temp_bulk_tag_input";
+
+impl TodoApp {
+    // Plain clicks focus a single note and drop the selection, Ctrl-click adds or removes the note and
+    // Shift-click selects every shown note between the last clicked one and this one.
+    pub fn click_note(&mut self, id: Uuid, modifiers: Modifiers, visible: &[Uuid]) {
+        if modifiers.shift {
+            let anchor = self.selection_anchor.or(self.focused_note)
+                .and_then(|anchor| visible.iter().position(|visible_id| *visible_id == anchor));
+            let clicked = visible.iter().position(|visible_id| *visible_id == id);
+
+            if let (Some(anchor), Some(clicked)) = (anchor, clicked) {
+                for visible_id in &visible[anchor.min(clicked)..=anchor.max(clicked)] {
+                    if !self.selected_notes.contains(visible_id) {
+                        self.selected_notes.push(*visible_id);
+                    }
+                }
+            }
+        } else if modifiers.command {
+            if let Some(position) = self.selected_notes.iter().position(|selected| *selected == id) {
+                self.selected_notes.remove(position);
+            } else {
+                self.selected_notes.push(id);
+            }
+            self.selection_anchor = Some(id);
+        } else {
+            self.selected_notes.clear();
+            self.selection_anchor = Some(id);
+        }
+
+        self.focused_note = Some(id);
+    }
+
+    pub fn select_all_notes(&mut self) {
+        self.selected_notes = self.state.visible_indices().into_iter().map(|index| self.state.list[index].id).collect();
+    }
+
+    // Selected notes that still exist on the page, in page order.
+    fn selected_indices(&self) -> Vec<usize> {
+        let mut indices: Vec<usize> = self.selected_notes.iter().filter_map(|id| self.state.index_of(*id)).collect();
+        indices.sort_unstable();
+        indices
+    }
+
+    pub fn render_bulk_bar(&mut self, ui: &mut Ui) {
+        self.selected_notes.retain(|id| self.state.index_of(*id).is_some());
+        let indices = self.selected_indices();
+        if indices.is_empty() {
+            return;
+        }
+
+        let check_parents = self.dark_mode.auto_check_parents;
+        let mut history_label = None;
+        let mut send_to = None;
+        let mut delete = false;
+
+        ui.horizontal_wrapped(|ui|{
+            ui.label(RichText::new(format!("{} selected", indices.len())).strong());
+
+            if ui.button("✔ Check").clicked() {
+                for &index in &indices {
+                    self.state.set_checked(index, true, check_parents);
+                }
+                history_label = Some("Check notes");
+            }
+
+            if ui.button("☐ Uncheck").clicked() {
+                for &index in &indices {
+                    self.state.set_checked(index, false, check_parents);
+                }
+                history_label = Some("Uncheck notes");
+            }
+
+            ui.menu_button("● Priority", |ui|{
+                for priority in Priority::ALL {
+                    if ui.button(priority.label()).clicked() {
+                        for &index in &indices {
+                            self.state.list[index].priority = priority;
+                        }
+                        history_label = Some("Set priority");
+                        ui.close_menu();
+                    }
+                }
+            });
+
+            ui.menu_button("🏷 Tag", |ui|{
+                if let Some(label) = self.render_bulk_tag_menu(ui, &indices) {
+                    history_label = Some(label);
+                    ui.close_menu();
+                }
+            });
+
+            ui.menu_button("📅 Due Date", |ui|{
+                due_date_editor(ui, &mut self.bulk_due, "bulk_due_date");
+                ui.add_space(PADDING);
+                if ui.button("Apply to Selected").clicked() {
+                    for &index in &indices {
+                        self.state.list[index].due = self.bulk_due;
+                    }
+                    history_label = Some("Set due date");
+                    ui.close_menu();
+                }
+            });
+
+            let other_pages: Vec<(Uuid, String)> = self.state_list.list.iter()
+                .filter(|page| !self.is_current_page(page.id))
+                .map(|page| (page.id, page.title.clone()))
+                .collect();
+            send_to = Self::render_send_menus(ui, &other_pages);
+
+            if ui.button("❌ Delete").clicked() {
+                delete = true;
+            }
+
+            if ui.button("✖ Clear Selection").clicked() {
+                self.selected_notes.clear();
+            }
+        });
+        ui.add_space(PADDING);
+        ui.separator();
+
+        if let Some(label) = history_label {
+            self.record_history(label);
+            self.update_state();
+        }
+
+        if let Some((page, copy)) = send_to {
+            self.send_notes(&indices, page, copy);
+        }
+
+        if delete {
+            self.record_history("Delete notes");
+            self.delete_content(&indices);
+            self.update_state();
+            self.selected_notes.clear();
+        }
+    }
+
+    // Returns the history label once tags were added or removed.
+    fn render_bulk_tag_menu(&mut self, ui: &mut Ui, indices: &[usize]) -> Option<&'static str> {
+        let mut pending_string = Self::read_temp_mem(ui.ctx(), TEMP_BULK_TAG_ID_NAME).unwrap_or_default();
+        let response = ui.add(TextEdit::singleline(&mut pending_string).hint_text("#tag").id(Id::new(TEMP_BULK_TAG_ID_NAME)));
+        Self::write_temp_mem(ui.ctx(), TEMP_BULK_TAG_ID_NAME, &pending_string);
+
+        let tag = normalize_tag(pending_string.trim())?;
+        let add = ui.button("Add to Selected").clicked() || (response.lost_focus() && Self::enter_key_pressed(ui));
+        let remove = ui.button("Remove from Selected").clicked();
+
+        if add {
+            for &index in indices {
+                if !self.state.list[index].has_tag(&tag) {
+                    self.state.list[index].tags.push(tag.clone());
+                }
+            }
+        } else if remove {
+            for &index in indices {
+                self.state.list[index].tags.retain(|own| *own != tag);
+            }
+        } else {
+            return None;
+        }
+
+        Self::write_temp_mem(ui.ctx(), TEMP_BULK_TAG_ID_NAME, "");
+        Some(if add {"Tag notes"} else {"Untag notes"})
+    }
+}
@@ -1,9 +1,68 @@
-use crate::todo_func::TodoApp;
+use crate::todo_func::{Content, Due, DueStatus, NoteFilter, NoteSort, Priority, TodoApp};
+use crate::ui::tags::tag_chip;
 
-use eframe::egui::{Label, Layout, RichText, Ui};
+use chrono::NaiveDateTime;
+use eframe::egui::{Button, CollapsingHeader, ComboBox, CursorIcon, DragAndDrop, Id, Label, Layout, Modifiers, RichText, Sense, Shape, Stroke, TextEdit, Ui, Vec2};
+use uuid::Uuid;
 
 // * The body's hitbox has a possibility to overlap the header's, resulting in weird focusing behaviors. This is a remedy.
 const NOTE_PADDING: f32 = 10.0;
+const TEMP_EDIT_ID_NAME: &str = "temp_edit_input";
+const EDIT_FIELD_ID_NAME: &str = "note_edit_field";
+const INDENT_WIDTH: f32 = 20.0;
+const ARROW_WIDTH: f32 = 14.0;
+const NOTE_FLASH_ID_NAME: &str = "note_flash";
+const NOTE_FLASH_SECONDS: f32 = 1.5;
+const SELECTED_NOTE_STRENGTH: f32 = 0.35;
+
+// What gets dragged around by a note's handle, kept apart from the page indices dragged in the side panel.
+// It names the note rather than its row, since the list can change before the note is dropped.
+pub struct DraggedNote(pub Uuid);
+
+// Everything the note rows change during one frame, applied to the app once they are all drawn.
+struct NoteListFrame {
+    editing_note: Option<Uuid>,
+    focused_note: Option<Uuid>,
+    focus_moved: bool,
+    selected_notes: Vec<Uuid>,
+    clicked_note: Option<(Uuid, Modifiers)>,
+    can_reorder: bool,
+    // Where the note being dragged currently sits, so a row can tell which side it would land on.
+    dragged_index: Option<usize>,
+    note_moved: Option<(Uuid, Uuid)>,
+    other_pages: Vec<(Uuid, String)>,
+    sent_note: Option<(Uuid, Uuid, bool)>,
+    // The note being flashed and how strongly it is still highlighted.
+    flash: Option<(Uuid, f32)>,
+    now: NaiveDateTime,
+    state_changed: bool,
+    history_label: Option<&'static str>,
+    due_date_note: Option<Uuid>,
+    tag_editor_note: Option<Uuid>,
+    tag_clicked: Option<String>,
+    checked_note: Option<(Uuid, bool)>,
+    indented_note: Option<(Uuid, bool)>,
+    content_to_delete: Vec<usize>,
+}
+
+impl NoteListFrame {
+    fn changed(&mut self, label: &'static str) {
+        self.state_changed = true;
+        self.history_label = Some(label);
+    }
+}
+
+// What was picked from a note's context menu.
+enum NoteMenuAction {
+    Edit,
+    SetDueDate,
+    EditTags,
+    SetPriority,
+    Indent,
+    Outdent,
+    // Page to send the note to, and whether it is copied rather than moved.
+    SendTo(Uuid, bool),
+}
 
 impl TodoApp {
     pub fn display_empty_content_prompt(ui: &mut Ui, to_print: &str){
@@ -26,42 +85,541 @@ impl TodoApp {
             return;
         }
 
-        let mut content_to_delete = Vec::<usize>::new();
+        self.render_note_toolbar(ui);
+        self.render_bulk_bar(ui);
+        let visible_indices = self.state.visible_indices();
+        let has_archived = !self.state.archived().is_empty();
+        if visible_indices.is_empty() && !has_archived {
+            Self::display_empty_content_prompt(ui, "🔍 No notes match the current filter.");
+            return;
+        }
+        if visible_indices.is_empty() {
+            ui.label(RichText::new("🎉 Nothing left to do here.").weak());
+        }
+        self.render_revealed_hint(ui);
+        let visible_ids: Vec<Uuid> = visible_indices.iter().map(|&index| self.state.list[index].id).collect();
 
-        for (index, content) in self.state.list.iter_mut().enumerate() {
+        let mut frame = NoteListFrame {
+            editing_note: self.editing_note,
+            focused_note: self.focused_note,
+            focus_moved: Self::take_note_focus_moved(ui.ctx()),
+            selected_notes: self.selected_notes.clone(),
+            clicked_note: None,
+            can_reorder: self.state.view.sort == NoteSort::Manual,
+            dragged_index: DragAndDrop::payload::<DraggedNote>(ui.ctx()).and_then(|note| self.state.index_of(note.0)),
+            note_moved: None,
+            other_pages: self.state_list.list.iter()
+                .filter(|page| !self.is_current_page(page.id))
+                .map(|page| (page.id, page.title.clone()))
+                .collect(),
+            sent_note: None,
+            flash: self.note_flash(ui.ctx()),
+            now: chrono::Local::now().naive_local(),
+            state_changed: false,
+            history_label: None,
+            due_date_note: None,
+            tag_editor_note: None,
+            tag_clicked: None,
+            checked_note: None,
+            indented_note: None,
+            content_to_delete: Vec::new(),
+        };
+
+        for index in visible_indices {
+            let progress = self.state.progress(index);
+            let content = &mut self.state.list[index];
+            ui.add_space(NOTE_PADDING);
+            Self::render_note_row(ui, index, content, progress, &mut frame);
             ui.add_space(NOTE_PADDING);
-            
-            ui.horizontal(|ui|{
-                // * Content
-                ui.with_layout(Layout::left_to_right(eframe::egui::Align::Min), |ui|{
-                    ui.set_width(ui.available_width() * 0.9); // Takes up only 90% of the available width
-                    ui.add_space(2.);
-                    ui.checkbox(&mut content.is_checked, String::new());
-
-                    if content.is_checked {
-                        ui.add(Label::new(RichText::new(&content.text).strikethrough()).wrap());
-                    } else {
-                        ui.add(Label::new(&content.text).wrap());
+            ui.separator();
+        }
+
+        self.editing_note = frame.editing_note;
+        self.focused_note = frame.focused_note;
+
+        if let Some((id, modifiers)) = frame.clicked_note {
+            self.click_note(id, modifiers, &visible_ids);
+        }
+
+        if let Some(id) = frame.due_date_note {
+            self.open_due_editor(id);
+        }
+
+        if let Some(id) = frame.tag_editor_note {
+            self.open_tag_editor(id);
+        }
+
+        if let Some(tag) = frame.tag_clicked.take() {
+            self.tag_view = Some(tag);
+        }
+
+        if let Some((index, checked)) = frame.checked_note.and_then(|(id, checked)| Some((self.state.index_of(id)?, checked))) {
+            let label = self.state.set_checked(index, checked, self.dark_mode.auto_check_parents);
+            frame.changed(label);
+        }
+
+        if let Some((index, page, copy)) = frame.sent_note.and_then(|(id, page, copy)| Some((self.state.index_of(id)?, page, copy))) {
+            self.send_notes(&[index], page, copy);
+        }
+
+        // Like pages, a note dragged upwards lands above the target and one dragged downwards lands below it.
+        if let Some((from, to)) = frame.note_moved.and_then(|(from, to)| Some((self.state.index_of(from)?, self.state.index_of(to)?))) {
+            if self.state.move_note(from, to, from < to).is_some() {
+                frame.changed("Move note");
+            }
+        }
+
+        if let Some((index, indent)) = frame.indented_note.and_then(|(id, indent)| Some((self.state.index_of(id)?, indent))) {
+            let changed = if indent { self.state.indent(index) } else { self.state.outdent(index) };
+            if changed {
+                frame.changed(if indent {"Indent note"} else {"Outdent note"});
+            }
+        }
+
+        if !frame.content_to_delete.is_empty() {
+            frame.history_label = Some("Delete note");
+        }
+
+        if let Some(label) = frame.history_label {
+            self.record_history(label);
+        }
+
+        if !frame.content_to_delete.is_empty() {
+            self.delete_content(&frame.content_to_delete);
+            frame.state_changed = true;
+        }
+
+        if frame.state_changed {
+            self.update_state();
+        }
+
+        self.render_archived_notes(ui);
+    }
+
+    // Says why a note the view would hide is in the list, and lets it go back to being hidden.
+    fn render_revealed_hint(&mut self, ui: &mut Ui) {
+        if self.state.revealed.is_none() {
+            return;
+        }
+
+        ui.horizontal(|ui|{
+            ui.label(RichText::new("👁 Showing a note the current view hides.").weak());
+            if ui.small_button("Hide it").clicked() {
+                self.state.revealed = None;
+            }
+        });
+    }
+
+    // Completed notes that were archived, listed below the rest of the page with their subtasks.
+    fn render_archived_notes(&mut self, ui: &mut Ui) {
+        let archived = self.state.archived();
+        if archived.is_empty() {
+            return;
+        }
+
+        let mut restored = None;
+        let mut deleted = None;
+
+        ui.add_space(NOTE_PADDING);
+        CollapsingHeader::new(format!("Completed ({})", archived.len())).id_source("archived_notes").show(ui, |ui|{
+            for root in archived {
+                for index in self.state.subtree(root) {
+                    let content = &self.state.list[index];
+                    ui.horizontal(|ui|{
+                        for _ in 0..content.depth {
+                            ui.add_space(INDENT_WIDTH);
+                        }
+                        ui.label(RichText::new(&content.text).strikethrough().weak());
+
+                        if index == root {
+                            ui.with_layout(Layout::right_to_left(eframe::egui::Align::Min), |ui|{
+                                if ui.button("❌").on_hover_text_at_pointer("Delete").clicked() {
+                                    deleted = Some(root);
+                                }
+                                if ui.button("↩").on_hover_text_at_pointer("Put back into the list").clicked() {
+                                    restored = Some(root);
+                                }
+                            });
+                        }
+                    });
+                }
+            }
+        });
+
+        if let Some(root) = restored {
+            self.state.unarchive(root);
+            self.record_history("Restore completed note");
+            self.update_state();
+        }
+
+        if let Some(root) = deleted {
+            self.record_history("Delete note");
+            self.delete_content(&[root]);
+            self.update_state();
+        }
+    }
+
+    // Page wide actions for reusing a page as a checklist.
+    fn render_completed_menu(&mut self, ui: &mut Ui) {
+        let completed = self.state.completed();
+        let can_archive = completed.iter().any(|&index| self.state.list[index].depth == 0 && !self.state.list[index].archived);
+        let any_checked = self.state.list.iter().any(|content| content.is_checked);
+
+        ui.menu_button("☑ Completed", |ui|{
+            if ui.add_enabled(can_archive, Button::new("🗄 Archive Completed")).clicked() {
+                if self.state.archive_completed() {
+                    self.record_history("Archive completed notes");
+                    self.update_state();
+                }
+                ui.close_menu();
+            }
+
+            if ui.add_enabled(!completed.is_empty(), Button::new("🗑 Clear Completed")).clicked() {
+                self.record_history("Clear completed notes");
+                self.delete_content(&completed);
+                self.update_state();
+                ui.close_menu();
+            }
+
+            if ui.add_enabled(any_checked, Button::new("↺ Reset All Checks")).clicked() {
+                if self.state.reset_checks() {
+                    self.record_history("Reset all checks");
+                    self.update_state();
+                }
+                ui.close_menu();
+            }
+        });
+    }
+
+    // The highlight starts fully on and fades out by itself, egui keeps repainting while it does.
+    pub fn start_note_flash(&mut self, ctx: &eframe::egui::Context, note: Uuid) {
+        ctx.animate_bool_with_time(Id::new(NOTE_FLASH_ID_NAME), true, 0.);
+        self.flash_note = Some(note);
+    }
+
+    fn note_flash(&mut self, ctx: &eframe::egui::Context) -> Option<(Uuid, f32)> {
+        let note = self.flash_note?;
+        let strength = ctx.animate_bool_with_time(Id::new(NOTE_FLASH_ID_NAME), false, NOTE_FLASH_SECONDS);
+        if strength <= 0. {
+            self.flash_note = None;
+        }
+        Some((note, strength))
+    }
+
+    fn render_note_row(ui: &mut Ui, index: usize, content: &mut Content, progress: Option<(usize, usize)>, frame: &mut NoteListFrame) {
+        // Reserved before the row so the flash can be painted behind it once its size is known.
+        let background = ui.painter().add(Shape::Noop);
+
+        let row = ui.horizontal(|ui|{
+            // * Content
+            ui.with_layout(Layout::left_to_right(eframe::egui::Align::Min), |ui|{
+                ui.set_width(ui.available_width() * 0.9); // Takes up only 90% of the available width
+                ui.add_space(2.);
+                ui.dnd_drag_source(Id::new(("note_drag", content.id)), DraggedNote(content.id), |ui|{
+                    ui.label(RichText::new("☰").weak());
+                }).response.on_hover_cursor(CursorIcon::Grab)
+                    .on_hover_text_at_pointer("Drag to reorder, or onto a page to move it there");
+                for _ in 0..content.depth {
+                    ui.add_space(INDENT_WIDTH);
+                }
+
+                // Only notes with subtasks get an arrow, the rest keep the same space so the checkboxes line up.
+                if progress.is_some() {
+                    let arrow = Button::new(if content.collapsed {"⏵"} else {"⏷"}).frame(false);
+                    let hover = if content.collapsed {"Show subtasks"} else {"Hide subtasks"};
+                    if ui.add_sized(Vec2::splat(ARROW_WIDTH), arrow).on_hover_text_at_pointer(hover).clicked() {
+                        content.collapsed = !content.collapsed;
+                        frame.state_changed = true;
+                    }
+                } else {
+                    ui.add_space(ARROW_WIDTH);
+                }
+
+                Self::render_note_content(ui, content, progress, frame);
+            });
+
+            ui.add_space(20.);
+
+            // * Buttons
+            ui.with_layout(Layout::right_to_left(eframe::egui::Align::Min), |ui|{
+                if ui.button("❌").on_hover_text_at_pointer("Delete Note").clicked() {
+                    frame.content_to_delete.push(index);
+                }
+                ui.add_space(2.);
+            });
+        }).response;
+
+        // Notes can only be reordered while the page shows their manual order.
+        if frame.can_reorder {
+            if let Some(from) = frame.dragged_index.filter(|from| *from != index && row.dnd_hover_payload::<DraggedNote>().is_some()) {
+                // The line shows which side of this note the dragged one will land on.
+                let y = if from > index { row.rect.top() - 4. } else { row.rect.bottom() + 4. };
+                let stroke = Stroke::new(2., ui.visuals().selection.bg_fill);
+                ui.painter().hline(row.rect.x_range(), y, stroke);
+            }
+
+            if let Some(from) = row.dnd_release_payload::<DraggedNote>().filter(|from| from.0 != content.id) {
+                frame.note_moved = Some((from.0, content.id));
+            }
+        }
+
+        let flash = frame.flash.filter(|(note, _)| *note == content.id).map(|(_, strength)| strength);
+        let selected = frame.selected_notes.contains(&content.id).then_some(SELECTED_NOTE_STRENGTH);
+        if let Some(strength) = flash.or(selected) {
+            let color = ui.visuals().selection.bg_fill.gamma_multiply(strength);
+            ui.painter().set(background, Shape::rect_filled(row.rect.expand(4.), 4., color));
+        }
+
+        if frame.focused_note == Some(content.id) {
+            let stroke = Stroke::new(1.5, ui.visuals().selection.bg_fill);
+            ui.painter().rect_stroke(row.rect.expand(4.), 4., stroke);
+            if frame.focus_moved {
+                row.scroll_to_me(None);
+            }
+        }
+    }
+
+    fn render_note_content(ui: &mut Ui, content: &mut Content, progress: Option<(usize, usize)>, frame: &mut NoteListFrame) {
+        let mut is_checked = content.is_checked;
+        if ui.checkbox(&mut is_checked, String::new()).changed() {
+            frame.checked_note = Some((content.id, is_checked));
+        }
+
+        if frame.editing_note == Some(content.id) {
+            let text_before = content.text.clone();
+            if Self::render_edit_field(ui, &mut content.text) {
+                frame.editing_note = None;
+                if content.text != text_before {
+                    frame.changed("Edit note");
+                }
+            }
+            return;
+        }
+
+        if let Some(color) = content.priority.color() {
+            ui.label(RichText::new("●").color(color))
+                .on_hover_text_at_pointer(format!("{} priority", content.priority.label()));
+        }
+
+        let due_status = content.due_status(frame.now);
+        let label = Label::new(Self::note_text(ui, content, due_status));
+        let label_response = ui.add(label.wrap().sense(Sense::click()))
+            .on_hover_text_at_pointer("Double-click to edit");
+
+        if label_response.clicked() {
+            frame.clicked_note = Some((content.id, ui.input(|i| i.modifiers)));
+        }
+
+        if let Some((done, total)) = progress {
+            ui.label(RichText::new(format!("{done}/{total} done")).small().weak());
+        }
+
+        if let Some(due) = content.due {
+            Self::render_due_chip(ui, due, due_status);
+        }
+
+        if let Some(recurrence) = content.recurrence {
+            ui.label(RichText::new(format!("🔁 {}", recurrence.label())).small().weak())
+                .on_hover_text_at_pointer("Checking it moves it to the next occurrence");
+        }
+
+        for tag in &content.tags {
+            if tag_chip(ui, tag) {
+                frame.tag_clicked = Some(tag.clone());
+            }
+        }
+
+        let mut start_editing = label_response.double_clicked();
+        let mut menu_action = None;
+        label_response.context_menu(|ui| menu_action = Self::render_note_menu(ui, content, frame.can_reorder, &frame.other_pages));
+        match menu_action {
+            Some(NoteMenuAction::Edit) => start_editing = true,
+            Some(NoteMenuAction::SetDueDate) => frame.due_date_note = Some(content.id),
+            Some(NoteMenuAction::EditTags) => frame.tag_editor_note = Some(content.id),
+            Some(NoteMenuAction::Indent) => frame.indented_note = Some((content.id, true)),
+            Some(NoteMenuAction::Outdent) => frame.indented_note = Some((content.id, false)),
+            Some(NoteMenuAction::SendTo(page, copy)) => frame.sent_note = Some((content.id, page, copy)),
+            Some(NoteMenuAction::SetPriority) => frame.changed("Set priority"),
+            None => {},
+        }
+
+        if start_editing {
+            frame.editing_note = Some(content.id);
+            Self::write_temp_mem(ui.ctx(), TEMP_EDIT_ID_NAME, &content.text);
+            ui.memory_mut(|mem| mem.request_focus(Id::new(EDIT_FIELD_ID_NAME)));
+        }
+    }
+
+    // Sorting and filtering only change how the page is shown, so they are saved without touching its modified time.
+    fn render_note_toolbar(&mut self, ui: &mut Ui) {
+        let view_before = self.state.view.clone();
+
+        let mut filters = vec![NoteFilter::All, NoteFilter::Overdue];
+        let mut page_tags: Vec<&String> = self.state.list.iter().flat_map(|content| &content.tags).collect();
+        page_tags.sort();
+        page_tags.dedup();
+        filters.extend(page_tags.into_iter().map(|tag| NoteFilter::Tagged(tag.clone())));
+
+        ui.horizontal_wrapped(|ui|{
+            ui.label("Sort: ");
+            ComboBox::from_id_source("note_sort")
+                .selected_text(self.state.view.sort.label())
+                .show_ui(ui, |ui|{
+                    for sort in NoteSort::ALL {
+                        ui.selectable_value(&mut self.state.view.sort, sort, sort.label());
                     }
                 });
 
-                ui.add_space(20.);
+            ui.label("Priority: ");
+            ComboBox::from_id_source("note_priority_filter")
+                .selected_text(Self::priority_filter_label(self.state.view.min_priority))
+                .show_ui(ui, |ui|{
+                    for priority in Priority::ALL {
+                        ui.selectable_value(&mut self.state.view.min_priority, priority, Self::priority_filter_label(priority));
+                    }
+                });
 
-                // * Buttons
-                ui.with_layout(Layout::right_to_left(eframe::egui::Align::Min), |ui|{
-                    if ui.button("❌").on_hover_text_at_pointer("Delete Note").clicked() {
-                        content_to_delete.push(index);
+            ui.label("Show: ");
+            ComboBox::from_id_source("note_filter")
+                .selected_text(self.state.view.filter.label())
+                .show_ui(ui, |ui|{
+                    for filter in filters {
+                        let label = filter.label();
+                        ui.selectable_value(&mut self.state.view.filter, filter, label);
+                    }
+                });
+
+            ui.checkbox(&mut self.state.view.hide_completed, "Hide completed");
+
+            if self.state.view.is_filtered() && ui.button("✖ Clear Filters").clicked() {
+                self.state.view.clear_filters();
+            }
+
+            self.render_completed_menu(ui);
+
+            if ui.button("⧉ Duplicates").on_hover_text_at_pointer("Find and merge duplicate notes").clicked() {
+                self.panel_manager.show_duplicates(true);
+            }
+        });
+        ui.add_space(NOTE_PADDING);
+
+        if self.state.view != view_before {
+            self.state.revealed = None;
+            let view = self.state.view.clone();
+            if let Some(page) = self.state_list.current_mut() {
+                page.state.view = view;
+            }
+        }
+    }
+
+    fn priority_filter_label(priority: Priority) -> String {
+        match priority {
+            Priority::None => String::from("All"),
+            Priority::Urgent => String::from("Urgent only"),
+            _ => format!("{} and above", priority.label()),
+        }
+    }
+
+    // Nesting follows the saved order of the notes, so it is only offered while they are shown in it.
+    fn render_note_menu(ui: &mut Ui, content: &mut Content, can_nest: bool, other_pages: &[(Uuid, String)]) -> Option<NoteMenuAction> {
+        let mut action = None;
+
+        if ui.button("✏ Edit").clicked() {
+            action = Some(NoteMenuAction::Edit);
+            ui.close_menu();
+        }
+        if ui.button("📅 Due Date & Repeat").clicked() {
+            action = Some(NoteMenuAction::SetDueDate);
+            ui.close_menu();
+        }
+        if ui.button("🏷 Edit Tags").clicked() {
+            action = Some(NoteMenuAction::EditTags);
+            ui.close_menu();
+        }
+        if ui.add_enabled(can_nest, Button::new("→ Make Subtask")).on_disabled_hover_text("Only while sorted manually").clicked() {
+            action = Some(NoteMenuAction::Indent);
+            ui.close_menu();
+        }
+        if content.depth > 0 && ui.add_enabled(can_nest, Button::new("← Move Out a Level")).on_disabled_hover_text("Only while sorted manually").clicked() {
+            action = Some(NoteMenuAction::Outdent);
+            ui.close_menu();
+        }
+        ui.menu_button("● Priority", |ui|{
+            for priority in Priority::ALL {
+                if ui.radio(content.priority == priority, priority.label()).clicked() {
+                    content.priority = priority;
+                    action = Some(NoteMenuAction::SetPriority);
+                    ui.close_menu();
+                }
+            }
+        });
+        if let Some((page, copy)) = Self::render_send_menus(ui, other_pages) {
+            action = Some(NoteMenuAction::SendTo(page, copy));
+        }
+
+        action
+    }
+
+    // "Move to Page" and "Copy to Page" submenus listing every other page. Returns the page that was
+    // picked and whether the notes should be copied rather than moved.
+    pub fn render_send_menus(ui: &mut Ui, other_pages: &[(Uuid, String)]) -> Option<(Uuid, bool)> {
+        let mut action = None;
+
+        for (label, copy) in [("➡ Move to Page", false), ("📋 Copy to Page", true)] {
+            ui.add_enabled_ui(!other_pages.is_empty(), |ui|{
+                ui.menu_button(label, |ui|{
+                    for (id, title) in other_pages {
+                        if ui.button(title).clicked() {
+                            action = Some((*id, copy));
+                            ui.close_menu();
+                        }
                     }
-                    ui.add_space(2.);
                 });
             });
+        }
 
-            ui.add_space(NOTE_PADDING);
-            ui.separator();
+        action
+    }
+
+    fn note_text(ui: &Ui, content: &Content, due_status: Option<DueStatus>) -> RichText {
+        let mut text = RichText::new(&content.text);
+        if content.is_checked {
+            text = text.strikethrough();
+        }
+        if due_status == Some(DueStatus::Overdue) {
+            text = text.color(ui.visuals().error_fg_color);
         }
+        text
+    }
+
+    // Overdue notes are shown in the error colour and the ones due today stand out in the warning colour.
+    fn render_due_chip(ui: &mut Ui, due: Due, due_status: Option<DueStatus>) {
+        let chip = RichText::new(format!("📅 {}", due.label())).small();
+        let chip = match due_status {
+            Some(DueStatus::Overdue) => chip.color(ui.visuals().error_fg_color),
+            Some(DueStatus::Today) => chip.color(ui.visuals().warn_fg_color).strong(),
+            _ => chip.weak(),
+        };
+        ui.label(chip);
+    }
+
+    // Returns true once the edit is finished, either committed with Enter or cancelled with Escape/clicking away.
+    fn render_edit_field(ui: &mut Ui, text: &mut String) -> bool {
+        let mut pending_string = Self::read_temp_mem(ui.ctx(), TEMP_EDIT_ID_NAME).unwrap_or_default();
+
+        let response = ui.add_sized(
+            [ui.available_width(), 14.],
+            TextEdit::singleline(&mut pending_string).id(Id::new(EDIT_FIELD_ID_NAME)));
 
-        self.delete_content(&mut content_to_delete);
-        self.update_state();
-        
+        Self::write_temp_mem(ui.ctx(), TEMP_EDIT_ID_NAME, &pending_string);
+
+        if !response.lost_focus() {
+            return false;
+        }
+
+        if Self::enter_key_pressed(ui) && !pending_string.trim().is_empty() {
+            *text = pending_string;
+        }
+        true
     }
 }
\ No newline at end of file
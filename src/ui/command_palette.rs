@@ -0,0 +1,182 @@
+use eframe::egui::{Align2, Button, Id, Key, Modifiers, TextEdit, Vec2, Window};
+use uuid::Uuid;
+
+use crate::shortcuts::Action;
+use crate::todo_func::TodoApp;
+
+const PADDING: f32 = 5.0;
+const MAX_RESULTS: usize = 12;
+const PALETTE_FIELD_ID_NAME: &str = "command_palette_field";
+
+#[derive(Default)]
+pub struct CommandPalette {
+    pub query: String,
+    pub selected: usize,
+    pub commands_only: bool,
+}
+
+#[derive(Clone, Copy)]
+enum PaletteEntry {
+    Action(Action),
+    Page(Uuid),
+}
+
+struct PaletteMatch {
+    entry: PaletteEntry,
+    label: String,
+    score: i32,
+}
+
+// Case-insensitive subsequence match. Runs of consecutive letters and letters starting a word score higher.
+fn fuzzy_score(query: &str, candidate: &str) -> Option<i32> {
+    let mut query_chars = query.chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .peekable();
+
+    let mut score = 0;
+    let mut previous_matched = false;
+    let mut previous_char = ' ';
+
+    for c in candidate.chars() {
+        let Some(&wanted) = query_chars.peek() else {
+            break;
+        };
+
+        if c.to_lowercase().eq(std::iter::once(wanted)) {
+            score += 1;
+            if previous_matched {
+                score += 3;
+            }
+            if !previous_char.is_alphanumeric() {
+                score += 2;
+            }
+            query_chars.next();
+            previous_matched = true;
+        } else {
+            previous_matched = false;
+        }
+        previous_char = c;
+    }
+
+    query_chars.peek().is_none().then_some(score)
+}
+
+impl TodoApp {
+    pub fn open_command_palette(&mut self, ctx: &eframe::egui::Context, commands_only: bool) {
+        self.command_palette = CommandPalette { commands_only, ..Default::default() };
+        self.panel_manager.show_command_palette(true);
+        ctx.memory_mut(|mem| mem.request_focus(Id::new(PALETTE_FIELD_ID_NAME)));
+    }
+
+    fn palette_matches(&self) -> Vec<PaletteMatch> {
+        let query = self.command_palette.query.as_str();
+        let mut matches = Vec::new();
+
+        if !self.command_palette.commands_only {
+            for page in &self.state_list.list {
+                if let Some(score) = fuzzy_score(query, &page.title) {
+                    matches.push(PaletteMatch { entry: PaletteEntry::Page(page.id), label: format!("📄 {}", page.title), score });
+                }
+            }
+        }
+
+        let commands = Action::ALL.into_iter()
+            .filter(|action| !matches!(action, Action::OpenPalette | Action::OpenCommandPalette));
+        for action in commands {
+            if let Some(score) = fuzzy_score(query, action.description()) {
+                matches.push(PaletteMatch { entry: PaletteEntry::Action(action), label: format!("⚡ {}", action.description()), score });
+            }
+        }
+
+        matches.sort_by_key(|palette_match| std::cmp::Reverse(palette_match.score));
+        matches.truncate(MAX_RESULTS);
+        matches
+    }
+
+    pub fn render_command_palette(&mut self, ctx: &eframe::egui::Context) {
+        if !self.panel_manager.command_palette_visible {
+            return;
+        }
+
+        // Taken before the text field gets to see them, so Enter does not make it lose focus.
+        let (up, down, enter, escape) = ctx.input_mut(|i| (
+            i.consume_key(Modifiers::NONE, Key::ArrowUp),
+            i.consume_key(Modifiers::NONE, Key::ArrowDown),
+            i.consume_key(Modifiers::NONE, Key::Enter),
+            i.consume_key(Modifiers::NONE, Key::Escape),
+        ));
+
+        if escape {
+            self.panel_manager.show_command_palette(false);
+            return;
+        }
+
+        let matches = self.palette_matches();
+        let last = matches.len().saturating_sub(1);
+        let palette = &mut self.command_palette;
+        if up {
+            palette.selected = palette.selected.saturating_sub(1);
+        }
+        if down {
+            palette.selected = (palette.selected + 1).min(last);
+        }
+        palette.selected = palette.selected.min(last);
+
+        let mut chosen = enter.then_some(palette.selected);
+        let hint = if palette.commands_only {"Type a command..."} else {"Type a page title or command..."};
+
+        Window::new("Command Palette").title_bar(false).resizable(false).collapsible(false)
+        .anchor(Align2::CENTER_TOP, Vec2::new(0., 60.)).fixed_size(Vec2::new(400., 0.))
+        .show(ctx, |ui|{
+            let response = ui.add(TextEdit::singleline(&mut self.command_palette.query)
+                .hint_text(hint)
+                .desired_width(f32::INFINITY)
+                .id(Id::new(PALETTE_FIELD_ID_NAME)));
+
+            if response.changed() {
+                self.command_palette.selected = 0;
+            }
+            if !response.has_focus() {
+                response.request_focus();
+            }
+
+            ui.add_space(PADDING);
+            ui.separator();
+
+            if matches.is_empty() {
+                ui.label("No matches.");
+            }
+
+            for (index, palette_match) in matches.iter().enumerate() {
+                let is_selected = index == self.command_palette.selected;
+                let shortcut = match palette_match.entry {
+                    PaletteEntry::Action(action) => self.dark_mode.keymap.shortcut_text(ui.ctx(), action),
+                    PaletteEntry::Page(_) => String::new(),
+                };
+
+                let button = Button::new(palette_match.label.as_str())
+                    .shortcut_text(shortcut)
+                    .selected(is_selected)
+                    .frame(is_selected);
+
+                if ui.add_sized(Vec2::new(ui.available_width(), 18.), button).clicked() {
+                    chosen = Some(index);
+                }
+            }
+        });
+
+        let Some(entry) = chosen.and_then(|index| matches.get(index)).map(|palette_match| palette_match.entry) else {
+            return;
+        };
+
+        self.panel_manager.show_command_palette(false);
+        match entry {
+            PaletteEntry::Action(action) => self.run_action(ctx, action),
+            PaletteEntry::Page(id) => {
+                self.state_list.current_page = Some(id);
+                self.show_updated_state();
+            },
+        }
+    }
+}
@@ -0,0 +1,198 @@
+use chrono::{Datelike, NaiveTime, Timelike};
+use eframe::egui::{ComboBox, DragValue, Layout, TextEdit, Ui, Window};
+use egui_extras::DatePickerButton;
+use uuid::Uuid;
+
+use crate::recurrence::Recurrence;
+use crate::reminders::Reminder;
+use crate::todo_func::{Due, TodoApp};
+
+const PADDING: f32 = 5.0;
+const REPEAT_KINDS: [&str; 5] = ["Never", "Every few days", "Weekdays", "Every few weeks", "Monthly"];
+
+// A note's due date, repeat rule and reminder while they are being changed from its context menu.
+pub struct DueEditor {
+    pub note: Uuid,
+    pub due: Option<Due>,
+    pub reminder: Option<Reminder>,
+    pub recurrence: Option<Recurrence>,
+    pub rule_text: String,
+    pub rule_error: Option<String>,
+}
+
+// Checkbox to turn the due date on, the date picker itself and an optional time of day.
+pub fn due_date_editor(ui: &mut Ui, due: &mut Option<Due>, id_source: &str) {
+    ui.horizontal(|ui|{
+        let mut has_due = due.is_some();
+        if ui.checkbox(&mut has_due, "Due").changed() {
+            *due = has_due.then(Due::today);
+        }
+
+        let Some(due) = due else {
+            return;
+        };
+
+        ui.add(DatePickerButton::new(&mut due.date).id_source(id_source));
+
+        let mut has_time = due.time.is_some();
+        if ui.checkbox(&mut has_time, "at").changed() {
+            due.time = has_time.then(|| NaiveTime::from_hms_opt(9, 0, 0).unwrap_or(NaiveTime::MIN));
+        }
+
+        if let Some(time) = &mut due.time {
+            let mut hour = time.hour();
+            let mut minute = time.minute();
+            ui.add(DragValue::new(&mut hour).range(0..=23).custom_formatter(|n, _| format!("{n:02}")));
+            ui.label(":");
+            ui.add(DragValue::new(&mut minute).range(0..=59).custom_formatter(|n, _| format!("{n:02}")));
+            *time = NaiveTime::from_hms_opt(hour, minute, 0).unwrap_or(*time);
+        }
+    });
+}
+
+const fn repeat_kind(recurrence: Option<Recurrence>) -> usize {
+    match recurrence {
+        None => 0,
+        Some(Recurrence::EveryDays(_)) => 1,
+        Some(Recurrence::Weekdays) => 2,
+        Some(Recurrence::EveryWeeks(_)) => 3,
+        Some(Recurrence::MonthlyOnDay(_)) => 4,
+    }
+}
+
+// Picks the kind of rule from a list, or takes a typed RRULE for anything the list can't express directly.
+fn recurrence_editor(ui: &mut Ui, editor: &mut DueEditor) {
+    let before = editor.recurrence;
+
+    ui.horizontal(|ui|{
+        ui.label("Repeat: ");
+        ComboBox::from_id_source("note_repeat_kind")
+            .selected_text(REPEAT_KINDS[repeat_kind(editor.recurrence)])
+            .show_ui(ui, |ui|{
+                for (kind, label) in REPEAT_KINDS.into_iter().enumerate() {
+                    if ui.selectable_label(repeat_kind(editor.recurrence) == kind, label).clicked() {
+                        let month_day = editor.due.map_or_else(|| Due::today().date.day(), |due| due.date.day());
+                        editor.recurrence = match kind {
+                            1 => Some(Recurrence::EveryDays(1)),
+                            2 => Some(Recurrence::Weekdays),
+                            3 => Some(Recurrence::EveryWeeks(1)),
+                            4 => Some(Recurrence::MonthlyOnDay(month_day)),
+                            _ => None,
+                        };
+                    }
+                }
+            });
+
+        match &mut editor.recurrence {
+            Some(Recurrence::EveryDays(days)) => { ui.add(DragValue::new(days).range(1..=365).prefix("every ").suffix(" days")); },
+            Some(Recurrence::EveryWeeks(weeks)) => { ui.add(DragValue::new(weeks).range(1..=52).prefix("every ").suffix(" weeks")); },
+            Some(Recurrence::MonthlyOnDay(day)) => { ui.add(DragValue::new(day).range(1..=31).prefix("on day ")); },
+            Some(Recurrence::Weekdays) | None => {},
+        }
+    });
+
+    if editor.recurrence != before {
+        editor.rule_text = editor.recurrence.map(Recurrence::to_rrule).unwrap_or_default();
+        editor.rule_error = None;
+    }
+
+    ui.horizontal(|ui|{
+        ui.label("RRULE: ");
+        let response = ui.add(TextEdit::singleline(&mut editor.rule_text).hint_text("FREQ=WEEKLY;INTERVAL=2"));
+        if response.changed() {
+            if editor.rule_text.trim().is_empty() {
+                editor.recurrence = None;
+                editor.rule_error = None;
+            } else {
+                match Recurrence::from_rrule(&editor.rule_text) {
+                    Ok(recurrence) => {
+                        editor.recurrence = Some(recurrence);
+                        editor.rule_error = None;
+                    },
+                    Err(error) => editor.rule_error = Some(error),
+                }
+            }
+        }
+    });
+
+    if let Some(error) = &editor.rule_error {
+        ui.label(format!("⚠ {error}. ⚠"));
+    }
+}
+
+// Reminders only make sense for notes with a due date, so the caller disables this without one.
+fn reminder_editor(ui: &mut Ui, reminder: &mut Option<Reminder>) {
+    ui.horizontal(|ui|{
+        let mut has_reminder = reminder.is_some();
+        if ui.checkbox(&mut has_reminder, "Remind me").changed() {
+            *reminder = has_reminder.then(Reminder::default);
+        }
+
+        if let Some(reminder) = reminder {
+            let before = reminder.minutes_before;
+            ui.add(DragValue::new(&mut reminder.minutes_before).range(0..=10080).suffix(" min before"));
+            // A new lead time means a new reminder, even if the old one was snoozed or closed.
+            if reminder.minutes_before != before {
+                *reminder = Reminder { minutes_before: reminder.minutes_before, ..Reminder::default() };
+            }
+        }
+    });
+}
+
+impl TodoApp {
+    pub fn open_due_editor(&mut self, note: Uuid) {
+        let Some(content) = self.state.list.iter().find(|content| content.id == note) else {
+            return;
+        };
+
+        self.due_editor = Some(DueEditor {
+            note,
+            due: content.due,
+            reminder: content.reminder,
+            recurrence: content.recurrence,
+            rule_text: content.recurrence.map(Recurrence::to_rrule).unwrap_or_default(),
+            rule_error: None,
+        });
+    }
+
+    pub fn render_due_editor(&mut self, ctx: &eframe::egui::Context) {
+        let Some(editor) = &mut self.due_editor else {
+            return;
+        };
+
+        let mut open = true;
+        let mut save = false;
+
+        Window::new("Due Date & Repeat").open(&mut open).collapsible(false).resizable(false).show(ctx, |ui|{
+            due_date_editor(ui, &mut editor.due, "note_due_date");
+            ui.add_space(PADDING);
+            recurrence_editor(ui, editor);
+            ui.add_space(PADDING);
+            ui.add_enabled_ui(editor.due.is_some(), |ui| reminder_editor(ui, &mut editor.reminder));
+            ui.add_space(PADDING);
+            ui.with_layout(Layout::right_to_left(eframe::egui::Align::Min), |ui|{
+                if ui.button("Done").clicked() {
+                    save = true;
+                }
+            });
+        });
+
+        if save {
+            // A repeating note needs a date to count its next occurrence from.
+            let (note, recurrence) = (editor.note, editor.recurrence);
+            let due = editor.due.or_else(|| recurrence.map(|_| Due::today()));
+            let reminder = editor.reminder.filter(|_| due.is_some());
+            if let Some(content) = self.state.list.iter_mut().find(|content| content.id == note) {
+                content.due = due;
+                content.recurrence = recurrence;
+                content.reminder = reminder;
+                self.record_history("Set due date");
+                self.update_state();
+            }
+        }
+
+        if save || !open {
+            self.due_editor = None;
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use eframe::egui::{RichText, ScrollArea, Window};
+use uuid::Uuid;
+
+use crate::todo_func::TodoApp;
+
+const PADDING: f32 = 5.0;
+
+impl TodoApp {
+    pub fn render_duplicates(&mut self, ctx: &eframe::egui::Context) {
+        if !self.panel_manager.duplicates_visible {
+            return;
+        }
+
+        let groups: Vec<Vec<Uuid>> = self.state.duplicate_groups(self.dark_mode.fuzzy_duplicates).into_iter()
+            .map(|group| group.into_iter().map(|index| self.state.list[index].id).collect())
+            .collect();
+        let mut to_merge = Vec::new();
+        let mut chosen = None;
+        let mut duplicates_visible = true;
+
+        Window::new("⧉ Duplicates").open(&mut duplicates_visible).default_width(350.).collapsible(false).show(ctx, |ui|{
+            if groups.is_empty() {
+                ui.label("No duplicates on this page.");
+                return;
+            }
+
+            ui.label("Merging keeps the first note of a group and folds the others into it.");
+            ui.add_space(PADDING);
+            ui.separator();
+
+            ScrollArea::vertical().max_height(400.).show(ui, |ui|{
+                for group in &groups {
+                    for (position, id) in group.iter().enumerate() {
+                        let Some(index) = self.state.index_of(*id) else {
+                            continue;
+                        };
+                        let text = RichText::new(&self.state.list[index].text);
+                        let text = if position == 0 { text.strong() } else { text };
+                        if ui.selectable_label(false, text).on_hover_text_at_pointer("Go to note").clicked() {
+                            chosen = Some(*id);
+                        }
+                    }
+
+                    if ui.button(format!("Merge {} Notes", group.len())).clicked() {
+                        to_merge.push(group.clone());
+                    }
+                    ui.separator();
+                }
+            });
+
+            ui.add_space(PADDING);
+            if ui.button("Merge All").clicked() {
+                to_merge.clone_from(&groups);
+            }
+        });
+
+        self.panel_manager.show_duplicates(duplicates_visible);
+
+        if !to_merge.is_empty() {
+            self.merge_duplicates(&to_merge);
+        }
+
+        if let Some((page, note)) = self.state_list.current_page.zip(chosen) {
+            self.go_to_note(ctx, page, note);
+        }
+    }
+}
@@ -1,14 +1,72 @@
-use eframe::egui::{self, Layout, RichText, Window};
+use chrono::{Days, Local, NaiveDateTime, NaiveTime};
+use eframe::egui::{self, Align2, Grid, Id, Layout, RichText, Window};
+use uuid::Uuid;
+use crate::shortcuts::Action;
 use crate::todo_func::TodoApp;
 
 const PADDING: f32 = 5.0;
+const REMINDER_SPACING: f32 = 130.0;
+// Snoozing until tomorrow brings the reminder back at this hour.
+const TOMORROW_HOUR: u32 = 9;
+
+enum ReminderChoice {
+    Snooze(NaiveDateTime),
+    Done,
+    Dismiss,
+}
 
 impl TodoApp {
     pub fn render_popups(&mut self, ctx: &eframe::egui::Context){
+        if self.panel_manager.shortcuts_visible {
+            Window::new("Keyboard Shortcuts").open(&mut self.panel_manager.shortcuts_visible).collapsible(false).resizable(false).show(ctx, |ui|{
+                Grid::new("shortcuts_grid").num_columns(2).striped(true).spacing([20., PADDING]).show(ui, |ui|{
+                    let bound = Action::ALL.into_iter().filter(|action| self.dark_mode.keymap.shortcut(*action).is_some());
+                    for action in bound {
+                        ui.label(action.description());
+                        ui.monospace(RichText::new(self.dark_mode.keymap.shortcut_text(ctx, action)).strong());
+                        ui.end_row();
+                    }
+                });
+            });
+        }
+
+        if let Some(recovery) = &self.recovery {
+            let mut dismissed = false;
+            Window::new("Saved Data Could Not Be Read.").collapsible(false).resizable(false).movable(true).show(ctx, |ui|{
+                ui.monospace("Your saved pages could not be read, so only the pages that were still intact have been loaded.");
+                ui.add_space(PADDING);
+                ui.monospace(format!("Recovered pages: {}", recovery.salvaged_pages));
+                ui.monospace(recovery.lost_pages.map_or_else(
+                    || String::from("Unreadable pages: unknown, the data is too damaged to count them"),
+                    |lost_pages| format!("Unreadable pages: {lost_pages}")));
+                ui.add_space(PADDING);
+                if let Some(path) = &recovery.backup_path {
+                    ui.monospace("A copy of the unreadable data was saved to:");
+                    ui.vertical_centered(|ui|{
+                        ui.monospace(RichText::new(path.display().to_string()).strong());
+                    });
+                } else {
+                    ui.monospace("A backup copy could not be written. Your old data will be overwritten once you continue.");
+                }
+                ui.add_space(PADDING);
+                ui.collapsing("Details", |ui|{
+                    ui.monospace(&recovery.error);
+                });
+                ui.add_space(PADDING);
+                if ui.button("Continue").clicked() {
+                    dismissed = true;
+                }
+            });
+
+            if dismissed {
+                self.recovery = None;
+            }
+        }
+
         if self.panel_manager.reset_popup_visible {
             let mut temp_show_popup = self.panel_manager.reset_popup_visible;
             Window::new("Confirm Clearing of Data.").title_bar(false).open(&mut temp_show_popup).resizable(false).movable(true).show(ctx, |ui|{
-                ui.monospace("Clearing data includes all notes and pages and cannot be reversed. Are you sure you want to delete your data?");
+                ui.monospace("Clearing data includes all notes and pages and can only be undone until the app is closed. Are you sure you want to delete your data?");
                 ui.add_space(PADDING);
                 ui.with_layout( Layout::left_to_right(egui::Align::Min),|ui|{
                     let yes = ui.button("Yes");
@@ -33,10 +91,10 @@ impl TodoApp {
                 ui.monospace("You are attempting to delete the page entitled:");
                 ui.add_space(PADDING);
                 ui.vertical_centered(|ui|{
-                    ui.monospace(RichText::new(self.state_list.current_app_state.to_string()).strong());
+                    ui.monospace(RichText::new(self.state_list.current_title().unwrap_or_default()).strong());
                 });
                 ui.add_space(PADDING);
-                ui.monospace("Doing so will also delete every note within it. Are you sure of this?");
+                ui.monospace("Doing so will also move every note within it to the trash. Are you sure of this?");
                 ui.with_layout( Layout::left_to_right(egui::Align::Min),|ui|{
                     let yes = ui.button("Yes");
                     let no = ui.button("No");
@@ -53,5 +111,65 @@ impl TodoApp {
 
             });
         }
+
+        self.render_reminders(ctx);
+    }
+
+    // Every reminder that came due gets its own small window, stacked up from the bottom right corner.
+    fn render_reminders(&mut self, ctx: &eframe::egui::Context) {
+        let now = Local::now().naive_local();
+        let tomorrow = (now.date() + Days::new(1)).and_time(NaiveTime::from_hms_opt(TOMORROW_HOUR, 0, 0).unwrap_or(NaiveTime::MIN));
+        let mut choices: Vec<(Uuid, Uuid, ReminderChoice)> = Vec::new();
+        let mut offset = -10.;
+
+        for fired in &self.reminders.active {
+            let Some(page) = self.state_list.page(fired.page) else {
+                continue;
+            };
+            let Some(content) = page.state.list.iter().find(|content| content.id == fired.note) else {
+                continue;
+            };
+
+            let mut open = true;
+            Window::new("⏰ Reminder").id(Id::new(("reminder", fired.note))).open(&mut open)
+            .anchor(Align2::RIGHT_BOTTOM, [-10., offset]).collapsible(false).resizable(false).show(ctx, |ui|{
+                ui.strong(&content.text);
+                ui.small(format!("On \"{}\"", page.title));
+                if let Some(due) = content.due {
+                    ui.small(format!("Due {}", due.label()));
+                }
+                if fired.missed {
+                    ui.small("⚠ Missed while the app was closed. ⚠");
+                }
+                ui.add_space(PADDING);
+
+                ui.horizontal(|ui|{
+                    ui.label("Snooze: ");
+                    let snoozes = [("10 min", now + chrono::Duration::minutes(10)), ("1 h", now + chrono::Duration::hours(1)), ("Tomorrow", tomorrow)];
+                    for (label, until) in snoozes {
+                        if ui.button(label).clicked() {
+                            choices.push((fired.page, fired.note, ReminderChoice::Snooze(until)));
+                        }
+                    }
+                    if ui.button("✔ Done").clicked() {
+                        choices.push((fired.page, fired.note, ReminderChoice::Done));
+                    }
+                });
+            });
+
+            if !open {
+                choices.push((fired.page, fired.note, ReminderChoice::Dismiss));
+            }
+            offset -= REMINDER_SPACING;
+        }
+
+        let check_parents = self.dark_mode.auto_check_parents;
+        for (page, note, choice) in choices {
+            match choice {
+                ReminderChoice::Snooze(until) => self.edit_note(page, note, None, |state, index| state.list[index].snooze_reminder(until)),
+                ReminderChoice::Done => self.edit_note(page, note, Some("Check note"), |state, index| { state.set_checked(index, true, check_parents); }),
+                ReminderChoice::Dismiss => self.edit_note(page, note, None, |state, index| state.list[index].dismiss_reminder()),
+            }
+        }
     }
 }
\ No newline at end of file
@@ -0,0 +1,186 @@
+use std::ops::Range;
+
+use eframe::egui::{text::LayoutJob, Id, Key, Modifiers, RichText, ScrollArea, TextEdit, TextFormat, TextStyle, Ui, Window};
+use uuid::Uuid;
+
+use crate::todo_func::TodoApp;
+
+const PADDING: f32 = 5.0;
+const SEARCH_FIELD_ID_NAME: &str = "global_search_field";
+
+#[derive(Default)]
+pub struct GlobalSearch {
+    pub query: String,
+}
+
+struct SearchResult {
+    note: Uuid,
+    text: String,
+    matched: Range<usize>,
+}
+
+struct PageResults {
+    page: Uuid,
+    title: String,
+    results: Vec<SearchResult>,
+}
+
+// Byte range of the first case-insensitive match of `query` in `text`.
+fn find_ignore_case(text: &str, query: &str) -> Option<Range<usize>> {
+    let query: Vec<char> = query.chars().flat_map(char::to_lowercase).collect();
+    if query.is_empty() {
+        return None;
+    }
+
+    text.char_indices().find_map(|(start, _)| {
+        let mut lowered = Vec::new();
+        for (offset, c) in text[start..].char_indices() {
+            lowered.extend(c.to_lowercase());
+            if lowered.len() >= query.len() {
+                return (lowered == query).then_some(start..start + offset + c.len_utf8());
+            }
+        }
+        None
+    })
+}
+
+fn highlighted(ui: &Ui, text: &str, matched: Range<usize>) -> LayoutJob {
+    let normal = TextFormat {
+        font_id: TextStyle::Body.resolve(ui.style()),
+        color: ui.visuals().text_color(),
+        ..Default::default()
+    };
+    let highlight = TextFormat {
+        background: ui.visuals().selection.bg_fill,
+        color: ui.visuals().strong_text_color(),
+        ..normal.clone()
+    };
+
+    let mut job = LayoutJob::default();
+    job.append(&text[..matched.start], 0., normal.clone());
+    job.append(&text[matched.clone()], 0., highlight);
+    job.append(&text[matched.end..], 0., normal);
+    job
+}
+
+impl TodoApp {
+    pub fn open_search(&mut self, ctx: &eframe::egui::Context) {
+        self.panel_manager.show_search(true);
+        ctx.memory_mut(|mem| mem.request_focus(Id::new(SEARCH_FIELD_ID_NAME)));
+    }
+
+    fn search_results(&self) -> Vec<PageResults> {
+        let query = self.search.query.trim();
+
+        self.state_list.list.iter().filter_map(|page| {
+            let results: Vec<SearchResult> = page.state.list.iter().filter_map(|content| {
+                let matched = find_ignore_case(&content.text, query)?;
+                Some(SearchResult { note: content.id, text: content.text.clone(), matched })
+            }).collect();
+
+            (!results.is_empty()).then(|| PageResults { page: page.id, title: page.title.clone(), results })
+        }).collect()
+    }
+
+    // Stays open after a result is picked, so several of them can be looked at in turn.
+    pub fn render_search(&mut self, ctx: &eframe::egui::Context) {
+        if !self.panel_manager.search_visible {
+            return;
+        }
+
+        // Enter and Escape belong to whichever other text field is being typed in.
+        let field_focused = ctx.memory(|mem| mem.has_focus(Id::new(SEARCH_FIELD_ID_NAME)));
+        let (enter, escape) = if field_focused {
+            ctx.input_mut(|i| (
+                i.consume_key(Modifiers::NONE, Key::Enter),
+                i.consume_key(Modifiers::NONE, Key::Escape),
+            ))
+        } else {
+            (false, false)
+        };
+
+        if escape {
+            self.panel_manager.show_search(false);
+            return;
+        }
+
+        let pages = self.search_results();
+        let mut chosen = if enter {
+            pages.first().and_then(|page| page.results.first().map(|result| (page.page, result.note)))
+        } else {
+            None
+        };
+        let mut search_visible = true;
+
+        Window::new("🔍 Search").open(&mut search_visible).default_width(350.).collapsible(false).show(ctx, |ui|{
+            ui.add(TextEdit::singleline(&mut self.search.query)
+                .hint_text("Search notes on every page...")
+                .desired_width(f32::INFINITY)
+                .id(Id::new(SEARCH_FIELD_ID_NAME)));
+
+            ui.add_space(PADDING);
+            ui.separator();
+
+            if self.search.query.trim().is_empty() {
+                return;
+            }
+
+            if pages.is_empty() {
+                ui.label("No matches.");
+                return;
+            }
+
+            ScrollArea::vertical().max_height(400.).show(ui, |ui|{
+                for page in &pages {
+                    ui.label(RichText::new(format!("{} ({})", page.title, page.results.len())).strong());
+                    for result in &page.results {
+                        if ui.selectable_label(false, highlighted(ui, &result.text, result.matched.clone())).clicked() {
+                            chosen = Some((page.page, result.note));
+                        }
+                    }
+                    ui.add_space(PADDING);
+                }
+            });
+        });
+
+        self.panel_manager.show_search(search_visible);
+
+        if let Some((page, note)) = chosen {
+            self.go_to_note(ctx, page, note);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::todo_func::{Content, Page};
+
+    #[test]
+    fn matches_ignore_case() {
+        assert_eq!(find_ignore_case("Buy MILK today", "milk"), Some(4..8));
+        assert_eq!(find_ignore_case("Café au lait", "CAFÉ"), Some(0..5));
+        assert_eq!(find_ignore_case("Ärger im Büro", "büro"), Some(10..15));
+        assert_eq!(find_ignore_case("Buy milk", "bread"), None);
+        assert_eq!(find_ignore_case("Buy milk", ""), None);
+    }
+
+    #[test]
+    fn results_are_grouped_by_page() {
+        let mut app = TodoApp::default();
+        for (title, notes) in [("Home", vec!["Buy milk", "Walk dog"]), ("Work", vec!["Send report"]), ("Shop", vec!["Milk", "Eggs"])] {
+            let mut page = Page::new(String::from(title));
+            page.state.list = notes.into_iter().map(|text| Content::new(String::from(text))).collect();
+            app.state_list.list.push(page);
+        }
+
+        app.search.query = String::from(" MILK ");
+        let pages = app.search_results();
+
+        let found: Vec<(&str, Vec<&str>)> = pages.iter()
+            .map(|page| (page.title.as_str(), page.results.iter().map(|result| result.text.as_str()).collect()))
+            .collect();
+        assert_eq!(found, vec![("Home", vec!["Buy milk"]), ("Shop", vec!["Milk"])]);
+        assert_eq!(pages[0].results[0].matched, 4..8);
+    }
+}
@@ -1,12 +1,15 @@
-use eframe::egui::{self, Layout, RichText, Window};
+use eframe::egui::{self, Button, DragValue, Grid, Layout, RichText, Ui, Window};
+use crate::shortcuts::Action;
 use crate::todo_func::TodoApp;
 
 const PADDING: f32 = 5.0;
 
 impl TodoApp {
     pub fn render_settings(&mut self, ctx: &eframe::egui::Context) {
+        let mut action_to_rebind = None;
+        let mut settings_visible = self.panel_manager.settings_visible;
 
-        Window::new("Settings").open(&mut self.panel_manager.settings_visible).fade_in(true).fade_out(true).min_width(200.)
+        Window::new("Settings").open(&mut settings_visible).fade_in(true).fade_out(true).min_width(200.)
         .show(ctx, |ui|{
             ui.add_space(PADDING);
             ui.with_layout(Layout::left_to_right(egui::Align::Min), |ui|{
@@ -14,10 +17,20 @@ impl TodoApp {
                 ui.vertical(|ui|{
                     ui.label("Theme: ");
                     ui.label("Clear Data: ");
+                    ui.label("Undo History: ");
+                    ui.label("Keep Trash For: ");
+                    ui.label("Subtasks: ");
+                    ui.label("Reminders: ");
+                    ui.label("Duplicates: ");
                 });
                 ui.vertical_centered_justified(|ui| {
                     let theme_btn = ui.button( if self.dark_mode.is_dark_mode {"🌙 Dark"} else {"🌞 Light"});
                     let reset_btn = ui.button("🔁Reset");
+                    ui.add(DragValue::new(&mut self.dark_mode.history_depth).range(1..=500).suffix(" steps"));
+                    let retention = ui.add(DragValue::new(&mut self.dark_mode.trash_retention_days).range(1..=365).suffix(" days"));
+                    ui.checkbox(&mut self.dark_mode.auto_check_parents, "Check parent when all are done");
+                    ui.checkbox(&mut self.dark_mode.desktop_notifications, "Also show desktop notifications");
+                    ui.checkbox(&mut self.dark_mode.fuzzy_duplicates, "Also catch near matches with typos");
     
                     if theme_btn.clicked() {
                         self.dark_mode.is_dark_mode = !self.dark_mode.is_dark_mode;
@@ -26,10 +39,20 @@ impl TodoApp {
                     if reset_btn.clicked() {
                         self.panel_manager.reset_popup_visible = true;
                     }
+
+                    // Only once the new value is settled, so dragging past a shorter one doesn't empty the trash.
+                    if retention.drag_stopped() || (retention.changed() && !retention.dragged()) {
+                        self.state_list.purge_trash(self.dark_mode.trash_retention_days);
+                    }
     
                 });
             }); 
 
+            ui.add_space(PADDING);
+            ui.collapsing("Key Bindings", |ui|{
+                action_to_rebind = self.render_keymap_editor(ui);
+            });
+
             ui.add_space(30.);
             ui.separator();
             ui.vertical_centered(|ui|{
@@ -39,7 +62,55 @@ impl TodoApp {
             
         });
 
+        self.panel_manager.show_settings(settings_visible);
+        if !settings_visible {
+            self.rebinding_action = None;
+        }
+
+        if let Some(action) = action_to_rebind {
+            self.start_rebinding(ctx, action);
+        }
+
         self.update_theme(ctx);
         
     }
+
+    // Returns the action whose binding button was clicked.
+    fn render_keymap_editor(&mut self, ui: &mut Ui) -> Option<Action> {
+        let mut clicked = None;
+
+        ui.small("Click a binding, then press the new key chord. Escape cancels.");
+        ui.add_space(PADDING);
+
+        Grid::new("keymap_grid").num_columns(2).striped(true).show(ui, |ui|{
+            for action in Action::ALL {
+                ui.label(action.description());
+
+                let text = if self.rebinding_action == Some(action) {
+                    String::from("Press a key...")
+                } else if let Some(shortcut) = self.dark_mode.keymap.shortcut(action) {
+                    ui.ctx().format_shortcut(&shortcut)
+                } else {
+                    String::from("Unbound")
+                };
+
+                if ui.add(Button::new(text).selected(self.rebinding_action == Some(action))).clicked() {
+                    clicked = Some(action);
+                }
+                ui.end_row();
+            }
+        });
+
+        let warning = Self::read_keymap_warning(ui.ctx());
+        if !warning.is_empty() {
+            ui.label(warning);
+        }
+
+        ui.add_space(PADDING);
+        if ui.add_enabled(!self.dark_mode.keymap.is_default(), Button::new("🔁 Restore Defaults")).clicked() {
+            self.dark_mode.keymap.reset();
+        }
+
+        clicked
+    }
 }
\ No newline at end of file
@@ -1,10 +1,16 @@
-use crate::todo_func::TodoApp;
-use eframe::egui::{self, Button, Frame, ScrollArea, SidePanel, TextEdit, TopBottomPanel, Vec2};
+use crate::todo_func::{Page, PageSort, TodoApp};
+use crate::ui::center_panel::DraggedNote;
+use uuid::Uuid;
+use eframe::egui::{self, Button, ComboBox, Frame, Id, ScrollArea, SidePanel, Stroke, TextEdit, TopBottomPanel, Ui, Vec2};
 
 const PADDING: f32 = 5.0;
 const NOTE_PADDING: f32 = 10.0;
 const TEMP_PAGE_INPUT_ID_NAME: &str = "temp_page_input";
 const TEMP_PAGE_INPUT_WARNING_ID_NAME: &str = "pages_warning_message";
+const TEMP_RENAME_INPUT_ID_NAME: &str = "temp_rename_input";
+const TEMP_RENAME_WARNING_ID_NAME: &str = "rename_warning_message";
+const RENAME_FIELD_ID_NAME: &str = "page_rename_field";
+const ADD_PAGE_FIELD_ID_NAME: &str = "page_add_field";
 
 impl TodoApp {
     pub fn render_side_panel(&mut self, ctx: &eframe::egui::Context){
@@ -29,6 +35,13 @@ impl TodoApp {
                     ui.monospace("...");
                     
                     ui.add_space(NOTE_PADDING);
+                    self.render_tag_list(ui);
+                    ui.add_space(PADDING);
+                    let trash_btn = ui.button(format!("♻ Trash ({})", self.state_list.trash.len()));
+                    if trash_btn.clicked() {
+                        self.panel_manager.show_trash(!self.panel_manager.trash_visible);
+                    }
+                    ui.add_space(PADDING);
                     if self.no_page_selected() {
                         ui.monospace("No Page Selected");
                     } else {
@@ -51,9 +64,10 @@ impl TodoApp {
                 Button::new("📝 New Page"));
             
             if add_button.clicked() {
-                self.panel_manager.add_page_panel_visible = !self.panel_manager.add_page_panel_visible;
                 if self.panel_manager.add_page_panel_visible {
-                    Self::write_temp_mem(ctx, TEMP_PAGE_INPUT_ID_NAME, "");
+                    self.panel_manager.show_add_page_panel(false);
+                } else {
+                    self.open_add_page_panel(ctx);
                 }
             }
 
@@ -67,7 +81,7 @@ impl TodoApp {
 
                 let response = ui.add_sized(
                     Vec2::new(ui.available_width(), 14.), 
-                    TextEdit::singleline(&mut pending_string).hint_text("Enter name of page"));
+                    TextEdit::singleline(&mut pending_string).hint_text("Enter name of page").id(Id::new(ADD_PAGE_FIELD_ID_NAME)));
                 
                 if response.lost_focus() && Self::enter_key_pressed(ui) {
                     string_entered = true;
@@ -76,10 +90,11 @@ impl TodoApp {
                 Self::write_temp_mem(ctx, TEMP_PAGE_INPUT_ID_NAME, &pending_string);
 
                 if string_entered {
-                    if pending_string.is_empty() || self.state_list.list.contains_key(&pending_string){
+                    if pending_string.is_empty() || self.state_list.contains_title(&pending_string){
                         Self::write_persist_state(ctx, TEMP_PAGE_INPUT_WARNING_ID_NAME, true);
                     } else {
-                        self.state_list.list.insert(pending_string, String::default());
+                        self.record_history("Add page");
+                        self.state_list.list.push(Page::new(pending_string));
                         self.panel_manager.show_add_page_panel(false);
                         Self::write_persist_state(ctx, TEMP_PAGE_INPUT_WARNING_ID_NAME, false);
                     }
@@ -102,41 +117,170 @@ impl TodoApp {
             ui.add_space(PADDING);
 
 
-            let mut page_title_clicked = false;
+            self.render_page_list(ui);
+
+            if to_delete_page {
+                self.panel_manager.show_delete_page_popup(true);
+            }
+        });
+
+
 
-            ScrollArea::vertical()
-            .scroll_bar_visibility(egui::scroll_area::ScrollBarVisibility::VisibleWhenNeeded)
-            .show(ui, |ui|{
-                for page_title in self.state_list.list.keys() {
-                    let mut title = page_title.clone();
-                    if self.is_current_page(page_title) {
-                        title = format!("➡{title}");
+    }
+
+    fn render_page_list(&mut self, ui: &mut Ui){
+        ui.horizontal(|ui|{
+            ui.label("Sort: ");
+            ComboBox::from_id_source("page_sort")
+                .width(ui.available_width())
+                .selected_text(self.state_list.page_sort.label())
+                .show_ui(ui, |ui|{
+                    for sort in PageSort::ALL {
+                        ui.selectable_value(&mut self.state_list.page_sort, sort, sort.label());
                     }
-    
+                });
+        });
+        ui.add_space(PADDING);
+
+        let mut page_clicked = None;
+        let mut page_moved = None;
+        let mut page_to_rename = None;
+        let mut note_dropped = None;
+        let can_reorder = self.state_list.page_sort == PageSort::Manual;
+
+        ScrollArea::vertical()
+        .scroll_bar_visibility(egui::scroll_area::ScrollBarVisibility::VisibleWhenNeeded)
+        .show(ui, |ui|{
+            for index in self.state_list.sorted_indices() {
+                let page = &self.state_list.list[index];
+                let id = page.id;
+                let mut title = page.title.clone();
+                if self.is_current_page(id) {
+                    title = format!("➡{title}");
+                }
+
+                if self.renaming_page == Some(id) {
                     ui.vertical_centered(|ui|{
-                        let page_btn = ui.add_sized(Vec2::new(ui.available_width() - 20., 18.), 
-                    Button::new(title).wrap_mode(egui::TextWrapMode::Truncate));
-    
-                        if page_btn.clicked() {
-                            self.state_list.current_app_state = page_title.to_string();
-                            page_title_clicked = true;
-                        }
+                        self.render_rename_field(ui, id);
                     });
-                    
+                    continue;
                 }
-            });
-            
 
-            if page_title_clicked {
-                self.show_updated_state();
-            }
+                ui.vertical_centered(|ui|{
+                    let size = Vec2::new(ui.available_width() - 20., 18.);
+                    let page_btn = Button::new(title).wrap_mode(egui::TextWrapMode::Truncate);
 
-            if to_delete_page {
-                self.panel_manager.show_delete_page_popup(true);
+                    // Pages can only be dragged around while the list shows their manual order.
+                    let response = if can_reorder {
+                        ui.dnd_drag_source(Id::new(("page_drag", id)), index, |ui|{
+                            ui.add_sized(size, page_btn)
+                        }).inner
+                    } else {
+                        ui.add_sized(size, page_btn)
+                    };
+
+                    if response.clicked() {
+                        page_clicked = Some(id);
+                    }
+
+                    response.context_menu(|ui|{
+                        if ui.button("✏ Rename").clicked() {
+                            page_to_rename = Some(id);
+                            ui.close_menu();
+                        }
+                    });
+
+                    if let Some(from) = response.dnd_hover_payload::<usize>().filter(|from| **from != index) {
+                        // The line shows which side of this page the dragged one will land on.
+                        let y = if *from > index { response.rect.top() } else { response.rect.bottom() };
+                        let stroke = Stroke::new(2., ui.visuals().selection.bg_fill);
+                        ui.painter().hline(response.rect.x_range(), y, stroke);
+                    }
+
+                    if let Some(from) = response.dnd_release_payload::<usize>().filter(|from| **from != index) {
+                        page_moved = Some((*from, index));
+                    }
+
+                    // Notes dropped onto another page are moved there, or copied while Ctrl is held.
+                    if !self.is_current_page(id) {
+                        if response.dnd_hover_payload::<DraggedNote>().is_some() {
+                            let stroke = Stroke::new(2., ui.visuals().selection.bg_fill);
+                            ui.painter().rect_stroke(response.rect, 2., stroke);
+                        }
+                        if let Some(note) = response.dnd_release_payload::<DraggedNote>() {
+                            note_dropped = Some((note.0, id, ui.input(|i| i.modifiers.command)));
+                        }
+                    }
+                });
+                
             }
         });
+        
+        if let Some((from, to)) = page_moved {
+            self.record_history("Move page");
+            self.state_list.move_page(from, to);
+        }
 
+        // The dropped note may have gone from the page since it was picked up.
+        if let Some((note, page, copy)) = note_dropped.and_then(|(id, page, copy)| Some((self.state.index_of(id)?, page, copy))) {
+            self.send_notes(&[note], page, copy);
+        }
+
+        if let Some(id) = page_to_rename {
+            self.start_renaming_page(ui.ctx(), id);
+        }
+
+        if let Some(id) = page_clicked {
+            self.state_list.current_page = Some(id);
+            self.show_updated_state();
+        }
+    }
+
+    pub fn open_add_page_panel(&mut self, ctx: &eframe::egui::Context){
+        self.panel_manager.show_add_page_panel(true);
+        Self::write_temp_mem(ctx, TEMP_PAGE_INPUT_ID_NAME, "");
+        ctx.memory_mut(|mem| mem.request_focus(Id::new(ADD_PAGE_FIELD_ID_NAME)));
+    }
+
+    pub fn start_renaming_page(&mut self, ctx: &eframe::egui::Context, id: Uuid){
+        let Some(page) = self.state_list.page(id) else {
+            return;
+        };
+
+        Self::write_temp_mem(ctx, TEMP_RENAME_INPUT_ID_NAME, &page.title);
+        Self::write_persist_state(ctx, TEMP_RENAME_WARNING_ID_NAME, false);
+        ctx.memory_mut(|mem| mem.request_focus(Id::new(RENAME_FIELD_ID_NAME)));
+        self.renaming_page = Some(id);
+    }
+
+    // Enter keeps the new title if it is valid, Escape or clicking away leaves the page as it was.
+    fn render_rename_field(&mut self, ui: &mut Ui, id: Uuid){
+        let mut pending_string = Self::read_temp_mem(ui.ctx(), TEMP_RENAME_INPUT_ID_NAME).unwrap_or_default();
+
+        let response = ui.add_sized(
+            Vec2::new(ui.available_width() - 20., 18.),
+            TextEdit::singleline(&mut pending_string).id(Id::new(RENAME_FIELD_ID_NAME)));
+
+        Self::write_temp_mem(ui.ctx(), TEMP_RENAME_INPUT_ID_NAME, &pending_string);
+
+        if response.lost_focus() {
+            if !Self::enter_key_pressed(ui) {
+                self.renaming_page = None;
+            } else if self.state_list.can_rename_page(id, &pending_string) {
+                self.record_history("Rename page");
+                self.state_list.rename_page(id, pending_string);
+                self.renaming_page = None;
+                Self::write_persist_state(ui.ctx(), TEMP_RENAME_WARNING_ID_NAME, false);
+            } else {
+                Self::write_persist_state(ui.ctx(), TEMP_RENAME_WARNING_ID_NAME, true);
+                response.request_focus();
+            }
+        }
 
+        let show_error = Self::read_persist_state(ui.ctx(), TEMP_RENAME_WARNING_ID_NAME).unwrap_or_default();
 
+        if show_error && self.renaming_page == Some(id) {
+            ui.label("⚠ Page title empty or already exists. ⚠");
+        }
     }
 }
\ No newline at end of file
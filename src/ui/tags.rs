@@ -0,0 +1,140 @@
+use eframe::egui::{Button, Layout, RichText, ScrollArea, TextEdit, Ui, Window};
+use uuid::Uuid;
+
+use crate::todo_func::{normalize_tag, TodoApp};
+
+const PADDING: f32 = 5.0;
+
+// A note's tags while they are being changed from its context menu, as one space separated line.
+pub struct TagEditor {
+    pub note: Uuid,
+    pub text: String,
+}
+
+// Small rounded button showing a tag. Returns true when it was clicked.
+pub fn tag_chip(ui: &mut Ui, tag: &str) -> bool {
+    ui.add(Button::new(RichText::new(format!("#{tag}")).small()).small().rounding(8.))
+        .on_hover_text_at_pointer("Show every note with this tag")
+        .clicked()
+}
+
+impl TodoApp {
+    pub fn open_tag_editor(&mut self, note: Uuid) {
+        let Some(content) = self.state.list.iter().find(|content| content.id == note) else {
+            return;
+        };
+
+        let text = content.tags.iter().map(|tag| format!("#{tag}")).collect::<Vec<_>>().join(" ");
+        self.tag_editor = Some(TagEditor { note, text });
+    }
+
+    pub fn render_tag_editor(&mut self, ctx: &eframe::egui::Context) {
+        let Some(editor) = &mut self.tag_editor else {
+            return;
+        };
+
+        let mut open = true;
+        let mut save = false;
+
+        Window::new("Edit Tags").open(&mut open).collapsible(false).resizable(false).show(ctx, |ui|{
+            let response = ui.add(TextEdit::singleline(&mut editor.text).hint_text("#work #later"));
+            if response.lost_focus() && Self::enter_key_pressed(ui) {
+                save = true;
+            }
+            ui.add_space(PADDING);
+            ui.with_layout(Layout::right_to_left(eframe::egui::Align::Min), |ui|{
+                if ui.button("Done").clicked() {
+                    save = true;
+                }
+            });
+        });
+
+        if save {
+            let mut tags: Vec<String> = Vec::new();
+            for tag in editor.text.split_whitespace().filter_map(normalize_tag) {
+                if !tags.contains(&tag) {
+                    tags.push(tag);
+                }
+            }
+
+            let note = editor.note;
+            if let Some(content) = self.state.list.iter_mut().find(|content| content.id == note) {
+                if content.tags != tags {
+                    content.tags = tags;
+                    self.record_history("Edit tags");
+                    self.update_state();
+                }
+            }
+        }
+
+        if save || !open {
+            self.tag_editor = None;
+        }
+    }
+
+    // Collapsible list of every tag in the side panel. Clicking one opens the notes carrying it.
+    pub fn render_tag_list(&mut self, ui: &mut Ui) {
+        let tag_counts = self.state_list.tag_counts();
+        if tag_counts.is_empty() {
+            return;
+        }
+
+        let mut tag_clicked = None;
+
+        ui.collapsing(format!("🏷 Tags ({})", tag_counts.len()), |ui|{
+            ScrollArea::vertical().id_source("tag_list").max_height(150.).show(ui, |ui|{
+                for (tag, count) in tag_counts {
+                    if ui.button(format!("#{tag} ({count})")).clicked() {
+                        tag_clicked = Some(tag.to_owned());
+                    }
+                }
+            });
+        });
+
+        if tag_clicked.is_some() {
+            self.tag_view = tag_clicked;
+        }
+    }
+
+    // Every note with the chosen tag, grouped by the page it is on.
+    pub fn render_tag_view(&mut self, ctx: &eframe::egui::Context) {
+        let Some(tag) = self.tag_view.clone() else {
+            return;
+        };
+
+        let mut open = true;
+        let mut note_clicked = None;
+
+        Window::new(format!("#{tag}")).id("tag_view".into()).open(&mut open).default_width(300.).show(ctx, |ui|{
+            ScrollArea::vertical().max_height(400.).show(ui, |ui|{
+                for page in &self.state_list.list {
+                    let notes: Vec<_> = page.state.list.iter().filter(|content| content.has_tag(&tag)).collect();
+                    if notes.is_empty() {
+                        continue;
+                    }
+
+                    ui.strong(&page.title);
+                    for content in notes {
+                        let text = if content.is_checked {
+                            RichText::new(&content.text).strikethrough()
+                        } else {
+                            RichText::new(&content.text)
+                        };
+                        if ui.selectable_label(false, text).clicked() {
+                            note_clicked = Some((page.id, content.id));
+                        }
+                    }
+                    ui.add_space(PADDING);
+                }
+            });
+        });
+
+        if let Some((page, note)) = note_clicked {
+            self.go_to_note(ctx, page, note);
+        }
+
+        if !open {
+            self.tag_view = None;
+        }
+    }
+}
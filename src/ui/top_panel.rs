@@ -1,11 +1,14 @@
-use eframe::egui::{self, Layout, TextEdit, TopBottomPanel, Ui, Vec2};
+use eframe::egui::{self, Button, Id, Layout, TextEdit, TopBottomPanel, Ui, Vec2};
 
-use crate::todo_func::{TodoApp, Content};
+use crate::shortcuts::Action;
+use crate::todo_func::{parse_tags, TodoApp, Content};
+use crate::ui::due_date::due_date_editor;
 
 const PADDING: f32 = 5.0;
 const NOTE_PADDING: f32 = 10.0;
 const TEMP_INPUT_ID_NAME: &str = "temp_input";
 const TEMP_INPUT_WARNING_ID_NAME: &str = "notes_warning_message";
+const ADD_FIELD_ID_NAME: &str = "note_add_field";
 
 
 impl TodoApp {
@@ -29,6 +32,12 @@ impl TodoApp {
                     .clicked() {
                         self.panel_manager.show_settings(true);
                     }
+
+                    ui.add_space(PADDING);
+
+                    ui.menu_button("Edit", |ui|{
+                        self.render_edit_menu(ui);
+                    });
                     
                 });
 
@@ -38,10 +47,11 @@ impl TodoApp {
 
                 ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui|{
                     let add_button = ui.button("➕ New Note");
-                    if add_button.clicked() && !self.no_page_selected(){
-                        self.panel_manager.add_panel_visible = !self.panel_manager.add_panel_visible;
+                    if add_button.clicked() {
                         if self.panel_manager.add_panel_visible {
-                            Self::write_temp_mem(ctx, TEMP_INPUT_ID_NAME, "");
+                            self.panel_manager.show_add_panel(false);
+                        } else {
+                            self.open_add_panel(ctx);
                         }
                     }
                 });
@@ -51,6 +61,40 @@ impl TodoApp {
         });
     }
 
+    fn render_edit_menu(&mut self, ui: &mut Ui) {
+        let undo_text = self.history.undo_label().map_or_else(|| String::from("⟲ Undo"), |label| format!("⟲ Undo {label}"));
+        let redo_text = self.history.redo_label().map_or_else(|| String::from("⟳ Redo"), |label| format!("⟳ Redo {label}"));
+        let can_undo = self.history.undo_label().is_some();
+        let can_redo = self.history.redo_label().is_some();
+
+        let undo_btn = ui.add_enabled(can_undo,
+            Button::new(undo_text).shortcut_text(self.dark_mode.keymap.shortcut_text(ui.ctx(), Action::Undo)));
+        let redo_btn = ui.add_enabled(can_redo,
+            Button::new(redo_text).shortcut_text(self.dark_mode.keymap.shortcut_text(ui.ctx(), Action::Redo)));
+
+        if undo_btn.clicked() {
+            self.undo();
+            ui.close_menu();
+        }
+
+        if redo_btn.clicked() {
+            self.redo();
+            ui.close_menu();
+        }
+    }
+
+    pub fn open_add_panel(&mut self, ctx: &eframe::egui::Context) {
+        if self.no_page_selected() {
+            return;
+        }
+
+        self.panel_manager.show_add_panel(true);
+        self.pending_due = None;
+        self.pending_duplicate = None;
+        Self::write_temp_mem(ctx, TEMP_INPUT_ID_NAME, "");
+        ctx.memory_mut(|mem| mem.request_focus(Id::new(ADD_FIELD_ID_NAME)));
+    }
+
     pub fn render_add_panel(&mut self, ui: &mut Ui, ctx: &eframe::egui::Context){
         let mut pending_string = Self::read_temp_mem(ctx, TEMP_INPUT_ID_NAME).unwrap_or_default();
         let mut string_entered = false;
@@ -60,25 +104,29 @@ impl TodoApp {
             ui.label("Enter content: ");
             let response = ui.add_sized(
                 Vec2::new(ui.available_width(), 14.), 
-                TextEdit::singleline(&mut pending_string));
+                TextEdit::singleline(&mut pending_string).hint_text("Add #tags anywhere").id(Id::new(ADD_FIELD_ID_NAME)));
             
             if response.lost_focus() && Self::enter_key_pressed(ui) {
                 string_entered = true;
             }
+
+            if response.changed() {
+                self.pending_duplicate = None;
+            }
         });
+        due_date_editor(ui, &mut self.pending_due, "add_panel_due_date");
         ui.add_space(NOTE_PADDING);
 
         Self::write_temp_mem(ctx, TEMP_INPUT_ID_NAME, &pending_string);
 
         if string_entered {
-            if pending_string.is_empty() {
-                Self::write_persist_state(ctx, TEMP_INPUT_WARNING_ID_NAME, true);
-            } else {
-                self.state.list.push(Content {text: pending_string, is_checked: false });
-                self.update_state();
-
-                self.panel_manager.show_add_panel(false);
-                Self::write_persist_state(ctx, TEMP_INPUT_WARNING_ID_NAME, false);
+            let (text, tags) = parse_tags(&pending_string);
+            Self::write_persist_state(ctx, TEMP_INPUT_WARNING_ID_NAME, text.is_empty());
+            if !text.is_empty() {
+                match self.state.find_duplicate(&text, self.dark_mode.fuzzy_duplicates) {
+                    Some(index) => self.pending_duplicate = Some(self.state.list[index].id),
+                    None => self.add_note(ctx, text, tags),
+                }
             }
         }
 
@@ -86,12 +134,61 @@ impl TodoApp {
 
         if show_error {
             ui.vertical_centered(|ui|{
-                ui.label("⚠ Invalid. Content is empty or already exists within this page. ⚠").highlight();
+                ui.label("⚠ Invalid. Content is empty. ⚠").highlight();
                 ui.add_space(PADDING); 
             });
 
         }
 
+        self.render_duplicate_prompt(ui, ctx, &pending_string);
+
         ui.separator();
     }
+
+    fn add_note(&mut self, ctx: &eframe::egui::Context, text: String, tags: Vec<String>) {
+        let mut content = Content::new(text);
+        content.due = self.pending_due.take();
+        content.tags = tags;
+        self.state.list.push(content);
+        self.record_history("Add note");
+        self.update_state();
+
+        self.panel_manager.show_add_panel(false);
+        self.pending_duplicate = None;
+        Self::write_persist_state(ctx, TEMP_INPUT_WARNING_ID_NAME, false);
+    }
+
+    // Asks what to do when the typed note matches one already on the page.
+    fn render_duplicate_prompt(&mut self, ui: &mut Ui, ctx: &eframe::egui::Context, pending_string: &str) {
+        let Some(existing) = self.pending_duplicate.and_then(|id| self.state.index_of(id)) else {
+            return;
+        };
+
+        let mut add_anyway = false;
+        let mut go_to_existing = false;
+
+        ui.vertical_centered(|ui|{
+            ui.label(format!("⚠ \"{}\" already exists within this page. ⚠", self.state.list[existing].text)).highlight();
+            ui.add_space(PADDING);
+            ui.horizontal(|ui|{
+                add_anyway = ui.button("Add Anyway").clicked();
+                go_to_existing = ui.button("Go to Existing").clicked();
+            });
+            ui.add_space(PADDING);
+        });
+
+        if add_anyway {
+            let (text, tags) = parse_tags(pending_string);
+            self.add_note(ctx, text, tags);
+        }
+
+        if go_to_existing {
+            let note = self.state.list[existing].id;
+            self.panel_manager.show_add_panel(false);
+            self.pending_duplicate = None;
+            if let Some(page) = self.state_list.current_page {
+                self.go_to_note(ctx, page, note);
+            }
+        }
+    }
 }
\ No newline at end of file
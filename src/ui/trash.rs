@@ -0,0 +1,97 @@
+use chrono::Local;
+use eframe::egui::{self, Layout, RichText, ScrollArea, Window};
+use crate::todo_func::{TodoApp, TrashedItem};
+
+const PADDING: f32 = 5.0;
+
+enum TrashAction {
+    Restore(usize),
+    DeleteForever(usize),
+    Empty,
+}
+
+impl TodoApp {
+    pub fn render_trash(&mut self, ctx: &eframe::egui::Context){
+        if !self.panel_manager.trash_visible {
+            return;
+        }
+
+        let mut action = None;
+
+        Window::new("Trash").open(&mut self.panel_manager.trash_visible).fade_in(true).fade_out(true)
+        .min_width(300.).default_height(300.)
+        .show(ctx, |ui|{
+            if self.state_list.trash.is_empty() {
+                ui.vertical_centered(|ui|{
+                    ui.label("🍃 Trash is empty.");
+                });
+                return;
+            }
+
+            ui.small(format!("Deleted items are removed for good after {} days.", self.dark_mode.trash_retention_days));
+            ui.add_space(PADDING);
+
+            ScrollArea::vertical()
+            .max_height(400.)
+            .scroll_bar_visibility(egui::scroll_area::ScrollBarVisibility::VisibleWhenNeeded)
+            .show(ui, |ui|{
+                // Most recently deleted first.
+                for (index, trashed) in self.state_list.trash.iter().enumerate().rev() {
+                    let (icon, text) = match &trashed.item {
+                        TrashedItem::Note(notes) => ("📝", notes.first().map_or("", |content| content.text.as_str())),
+                        TrashedItem::Page(page) => ("📄", page.title.as_str()),
+                    };
+
+                    ui.horizontal(|ui|{
+                        ui.with_layout(Layout::right_to_left(egui::Align::Min), |ui|{
+                            if ui.button("❌").on_hover_text_at_pointer("Delete Forever").clicked() {
+                                action = Some(TrashAction::DeleteForever(index));
+                            }
+                            if ui.button("↩").on_hover_text_at_pointer("Restore").clicked() {
+                                action = Some(TrashAction::Restore(index));
+                            }
+
+                            ui.with_layout(Layout::top_down(egui::Align::Min), |ui|{
+                                ui.label(format!("{icon} {text}"));
+                                let deleted = trashed.deleted.with_timezone(&Local).format("%Y-%m-%d %H:%M");
+                                let origin = match trashed.item {
+                                    TrashedItem::Note(ref notes) if notes.len() > 1 => {
+                                        format!("From \"{}\" with {} subtasks, deleted {deleted}", trashed.origin_title, notes.len() - 1)
+                                    },
+                                    TrashedItem::Note(_) => format!("From \"{}\", deleted {deleted}", trashed.origin_title),
+                                    TrashedItem::Page(_) => format!("Page, deleted {deleted}"),
+                                };
+                                ui.label(RichText::new(origin).small().weak());
+                            });
+                        });
+                    });
+                    ui.separator();
+                }
+            });
+
+            ui.add_space(PADDING);
+            ui.vertical_centered_justified(|ui|{
+                if ui.button("🗑 Empty Trash").clicked() {
+                    action = Some(TrashAction::Empty);
+                }
+            });
+        });
+
+        match action {
+            Some(TrashAction::Restore(index)) => {
+                self.record_history("Restore from trash");
+                self.state_list.restore_from_trash(index);
+                self.show_updated_state();
+            },
+            Some(TrashAction::DeleteForever(index)) => {
+                self.record_history("Delete forever");
+                self.state_list.delete_from_trash(index);
+            },
+            Some(TrashAction::Empty) => {
+                self.record_history("Empty trash");
+                self.state_list.trash.clear();
+            },
+            None => {},
+        }
+    }
+}